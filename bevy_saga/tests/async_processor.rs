@@ -0,0 +1,111 @@
+use bevy::app::TaskPoolPlugin;
+use bevy::prelude::{App, Res, ResMut, Resource, Update};
+use bevy_saga::prelude::PendingTasks;
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+use std::cell::Cell;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+#[derive(Default)]
+struct GateState {
+    open: bool,
+    waker: Option<Waker>,
+}
+
+#[derive(Clone, Default, Resource)]
+struct Gate(Arc<Mutex<GateState>>);
+
+impl Gate {
+    fn open(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.open = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for Gate {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.0.lock().unwrap();
+        if state.open {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[derive(Default, Resource)]
+struct Walked(Vec<u8>);
+
+#[saga_event]
+struct FindPath(u8);
+
+#[saga_event]
+struct PathFound(u8);
+
+fn find_path(FindPath(target): FindPath, gate: Res<Gate>) -> impl Future<Output = PathFound> + use<> {
+    let gate = gate.clone();
+    async move {
+        gate.await;
+        PathFound(target)
+    }
+}
+
+fn walk(PathFound(target): PathFound, mut walked: ResMut<Walked>) {
+    walked.0.push(target);
+}
+
+#[test]
+fn async_processor_continues_when_the_task_completes() {
+    let mut app = App::new();
+    app.add_plugins(TaskPoolPlugin::default());
+    app.init_resource::<Gate>();
+    app.init_resource::<Walked>();
+    app.add_saga(Update, (find_path, walk));
+
+    app.world_mut().send_event(FindPath(3));
+    app.update();
+    app.update();
+    assert_eq!(1, app.world().resource::<PendingTasks<PathFound>>().len());
+    assert!(app.world().resource::<Walked>().0.is_empty());
+
+    app.world().resource::<Gate>().open();
+    app.update();
+    app.update();
+    assert_eq!(vec![3], app.world().resource::<Walked>().0);
+    assert!(app.world().resource::<PendingTasks<PathFound>>().is_empty());
+}
+
+fn find_counted_path(FindPath(target): FindPath, gate: Res<Gate>) -> impl Future<Output = PathFound> + use<> {
+    let gate = gate.clone();
+    async move {
+        // A Cell that is held across an await makes the future Send, but not Sync.
+        let steps = Cell::new(target);
+        gate.await;
+        steps.set(steps.get() + 1);
+        PathFound(steps.get())
+    }
+}
+
+#[test]
+fn async_processor_accepts_futures_that_are_not_sync() {
+    let mut app = App::new();
+    app.add_plugins(TaskPoolPlugin::default());
+    app.init_resource::<Gate>();
+    app.init_resource::<Walked>();
+    app.add_saga(Update, (find_counted_path, walk));
+
+    app.world_mut().send_event(FindPath(3));
+    app.update();
+    app.world().resource::<Gate>().open();
+    app.update();
+    app.update();
+    assert_eq!(vec![4], app.world().resource::<Walked>().0);
+}
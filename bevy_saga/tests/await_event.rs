@@ -0,0 +1,127 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{App, ResMut, Resource, Trigger, Update};
+use bevy_saga::prelude::{
+    await_event, Awaited, Correlated, PendingAwaits, SagaCompleted, SagaInstance, SagaInstanceId,
+    SagaStarted,
+};
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Dialogs {
+    opened: HashMap<u8, SagaInstanceId>,
+    chosen: HashMap<u8, (SagaInstanceId, u8)>,
+}
+
+#[saga_event]
+struct Talk(u8);
+
+#[saga_event]
+struct DialogOpened(u8);
+
+#[saga_event]
+struct Choice {
+    speaker: u8,
+    option: u8,
+}
+
+impl Correlated for DialogOpened {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Correlated for Choice {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.speaker
+    }
+}
+
+fn open_dialog(Talk(speaker): Talk, instance: SagaInstance, mut dialogs: ResMut<Dialogs>) -> DialogOpened {
+    dialogs.opened.insert(speaker, instance.id());
+    DialogOpened(speaker)
+}
+
+fn continue_dialog(
+    Awaited(DialogOpened(speaker), Choice { option, .. }): Awaited<DialogOpened, Choice>,
+    instance: SagaInstance,
+    mut dialogs: ResMut<Dialogs>,
+) {
+    dialogs.chosen.insert(speaker, (instance.id(), option));
+}
+
+#[test]
+fn saga_resumes_when_the_awaited_event_arrives() {
+    let mut app = App::new();
+    app.init_resource::<Dialogs>();
+    app.add_saga(Update, (open_dialog, await_event::<DialogOpened, Choice>(), continue_dialog));
+
+    app.world_mut().send_event(Talk(1));
+    app.world_mut().send_event(Talk(2));
+    app.update();
+    assert_eq!(2, app.world().resource::<PendingAwaits<DialogOpened, Choice>>().len());
+    assert!(app.world().resource::<Dialogs>().chosen.is_empty());
+
+    app.update();
+    assert_eq!(2, app.world().resource::<PendingAwaits<DialogOpened, Choice>>().len());
+
+    app.world_mut().send_event(Choice { speaker: 2, option: 7 });
+    app.world_mut().send_event(Choice { speaker: 3, option: 9 });
+    app.update();
+    let pending = app.world().resource::<PendingAwaits<DialogOpened, Choice>>();
+    assert_eq!(1, pending.len());
+    assert!(pending.contains_key(&1));
+
+    let dialogs = app.world().resource::<Dialogs>();
+    assert_eq!(1, dialogs.chosen.len());
+    assert_eq!((dialogs.opened[&2], 7), dialogs.chosen[&2]);
+}
+
+#[derive(Default, Resource)]
+struct Lifecycle {
+    started: usize,
+    completed: usize,
+}
+
+#[test]
+fn awaited_events_dont_start_saga_instances() {
+    let mut app = App::new();
+    app.init_resource::<Dialogs>();
+    app.init_resource::<Lifecycle>();
+    app.add_saga(Update, (open_dialog, await_event::<DialogOpened, Choice>(), continue_dialog));
+    app.add_observer(|_: Trigger<SagaStarted>, mut lifecycle: ResMut<Lifecycle>| {
+        lifecycle.started += 1;
+    });
+    app.add_observer(|_: Trigger<SagaCompleted>, mut lifecycle: ResMut<Lifecycle>| {
+        lifecycle.completed += 1;
+    });
+
+    app.world_mut().send_event(Talk(1));
+    app.update();
+    app.world_mut().send_event(Choice { speaker: 1, option: 7 });
+    app.world_mut().send_event(Choice { speaker: 2, option: 9 });
+    app.update();
+
+    let lifecycle = app.world().resource::<Lifecycle>();
+    assert_eq!(1, lifecycle.started);
+    assert_eq!(1, lifecycle.completed);
+}
+
+#[test]
+fn saga_resumes_with_an_event_of_the_same_update() {
+    let mut app = App::new();
+    app.init_resource::<Dialogs>();
+    app.add_saga(Update, (open_dialog, await_event::<DialogOpened, Choice>(), continue_dialog));
+
+    app.world_mut().send_event(Talk(1));
+    app.world_mut().send_event(Choice { speaker: 1, option: 7 });
+    app.update();
+
+    let dialogs = app.world().resource::<Dialogs>();
+    assert_eq!((dialogs.opened[&1], 7), dialogs.chosen[&1]);
+    assert!(app.world().resource::<PendingAwaits<DialogOpened, Choice>>().is_empty());
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::{App, Commands, Entity, Events, ResMut, Resource, Update};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{
+    Awaited, Correlated, PendingAwaits, SagaCancelled, SagaControl, SagaInstance, SagaInstanceId,
+    await_event,
+};
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Log {
+    started: Vec<SagaInstanceId>,
+    finished: Vec<u8>,
+}
+
+#[saga_event]
+struct Greet(u8);
+
+#[saga_event]
+struct Talk(u8);
+
+#[saga_event]
+struct Choice(u8);
+
+#[saga_event]
+struct Attack(Entity);
+
+#[saga_event]
+struct Damage(Entity);
+
+impl Correlated for Talk {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Correlated for Choice {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+fn talk(Greet(speaker): Greet, instance: SagaInstance, mut log: ResMut<Log>) -> Talk {
+    log.started.push(instance.id());
+    Talk(speaker)
+}
+
+fn choose(Awaited(talk, _): Awaited<Talk, Choice>, mut log: ResMut<Log>) {
+    log.finished.push(talk.0);
+}
+
+fn cancelled(app: &mut App) -> Vec<SagaInstanceId> {
+    app.world_mut()
+        .resource_mut::<Events<SagaCancelled>>()
+        .drain()
+        .map(|cancelled| cancelled.instance)
+        .collect()
+}
+
+#[test]
+fn cancels_a_suspended_instance() {
+    let mut app = App::new();
+    app.init_resource::<Log>();
+    app.add_saga(Update, (talk, await_event::<Talk, Choice>(), choose));
+    app.world_mut().send_event(Greet(1));
+    app.world_mut().send_event(Greet(2));
+    app.update();
+
+    let instance = app.world().resource::<Log>().started[0];
+    app.world_mut().cancel_instance(instance);
+    assert_eq!(cancelled(&mut app), vec![instance]);
+    assert_eq!(app.world().resource::<PendingAwaits<Talk, Choice>>().len(), 1);
+
+    app.world_mut().send_event(Choice(1));
+    app.world_mut().send_event(Choice(2));
+    app.update();
+    assert_eq!(app.world().resource::<Log>().finished, vec![2]);
+}
+
+fn attack(Attack(attacker): Attack, instance: SagaInstance, mut commands: Commands) -> Damage {
+    commands.set_instance_owner(instance.id(), attacker);
+    Damage(attacker)
+}
+
+fn stun(Damage(attacker): Damage, mut commands: Commands) {
+    commands.cancel_owned_instances(attacker);
+}
+
+fn take_damage(_: Damage, mut log: ResMut<Log>) {
+    log.finished.push(0);
+}
+
+#[test]
+fn cancels_the_running_instances_of_an_owner() {
+    let mut app = App::new();
+    app.init_resource::<Log>();
+    app.add_saga(Update, (attack, (stun, take_damage)));
+    let attacker = app.world_mut().spawn_empty().id();
+    app.world_mut().send_event(Attack(attacker));
+    app.update();
+
+    assert!(app.world().resource::<Log>().finished.is_empty());
+    assert_eq!(cancelled(&mut app).len(), 1);
+}
+
+fn listen(Talk(speaker): Talk, mut log: ResMut<Log>) {
+    log.finished.push(speaker);
+}
+
+#[test]
+fn ignores_instances_that_already_finished() {
+    let mut app = App::new();
+    app.init_resource::<Log>();
+    app.add_saga(Update, (talk, listen));
+    app.world_mut().send_event(Greet(1));
+    app.update();
+
+    let instance = app.world().resource::<Log>().started[0];
+    assert_eq!(app.world().resource::<Log>().finished, vec![1]);
+    app.world_mut().cancel_instance(instance);
+    assert!(cancelled(&mut app).is_empty());
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::{App, ResMut, Resource, Update};
+use bevy_saga::prelude::{CompensateStage, ErrStage, OkStage};
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Log(Vec<&'static str>);
+
+#[saga_event]
+struct Craft(bool);
+
+#[saga_event]
+struct Reserved(bool);
+
+#[saga_event]
+struct Paid(bool);
+
+#[saga_event]
+struct Crafted;
+
+#[saga_event]
+struct Failed;
+
+fn reserve(Craft(succeeds): Craft, mut log: ResMut<Log>) -> Reserved {
+    log.0.push("reserve");
+    Reserved(succeeds)
+}
+
+fn release(_: Reserved, mut log: ResMut<Log>) {
+    log.0.push("release");
+}
+
+fn pay(Reserved(succeeds): Reserved, mut log: ResMut<Log>) -> Paid {
+    log.0.push("pay");
+    Paid(succeeds)
+}
+
+fn refund(_: Paid, mut log: ResMut<Log>) {
+    log.0.push("refund");
+}
+
+fn craft(Paid(succeeds): Paid, mut log: ResMut<Log>) -> Result<Crafted, Failed> {
+    log.0.push("craft");
+    if succeeds { Ok(Crafted) } else { Err(Failed) }
+}
+
+fn crafted(_: Crafted, mut log: ResMut<Log>) {
+    log.0.push("crafted");
+}
+
+fn failed(_: Failed, mut log: ResMut<Log>) {
+    log.0.push("failed");
+}
+
+fn test(succeeds: bool) -> Vec<&'static str> {
+    let mut app = App::new();
+    app.init_resource::<Log>();
+    app.add_saga(
+        Update,
+        (
+            reserve.compensate(release),
+            pay.compensate(refund),
+            craft.ok(crafted).err(failed),
+        ),
+    );
+    app.world_mut().send_event(Craft(succeeds));
+    app.update();
+    app.world_mut().resource_mut::<Log>().0.drain(..).collect()
+}
+
+#[test]
+fn compensations_run_in_reverse_order_on_err() {
+    assert_eq!(
+        vec!["reserve", "pay", "craft", "refund", "release", "failed"],
+        test(false)
+    );
+}
+
+#[test]
+fn compensations_are_skipped_on_ok() {
+    assert_eq!(vec!["reserve", "pay", "craft", "crafted"], test(true));
+}
@@ -0,0 +1,58 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{App, ResMut, Resource, Update};
+use bevy_saga::prelude::{ErrStage, OkStage, SagaInstance, SagaInstanceId};
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Instances {
+    triggered: HashMap<u8, SagaInstanceId>,
+    damaged: HashMap<u8, SagaInstanceId>,
+    missed: HashMap<u8, SagaInstanceId>,
+}
+
+#[saga_event]
+struct AttackTrigger(u8);
+
+#[saga_event]
+struct Damage(u8);
+
+#[saga_event]
+struct Missed(u8);
+
+fn attack(
+    AttackTrigger(n): AttackTrigger,
+    instance: SagaInstance,
+    mut instances: ResMut<Instances>,
+) -> Result<Damage, Missed> {
+    instances.triggered.insert(n, instance.id());
+    if n % 2 == 0 { Ok(Damage(n)) } else { Err(Missed(n)) }
+}
+
+fn take_damage(Damage(n): Damage, instance: SagaInstance, mut instances: ResMut<Instances>) {
+    instances.damaged.insert(n, instance.id());
+}
+
+fn miss(Missed(n): Missed, instance: SagaInstance, mut instances: ResMut<Instances>) {
+    instances.missed.insert(n, instance.id());
+}
+
+#[test]
+fn every_event_of_a_saga_instance_carries_its_id() {
+    let mut app = App::new();
+    app.init_resource::<Instances>();
+    app.add_saga(Update, attack.ok(take_damage).err(miss));
+    for n in 0..3 {
+        app.world_mut().send_event(AttackTrigger(n));
+    }
+    app.update();
+
+    let instances = app.world().resource::<Instances>();
+    assert_eq!(3, instances.triggered.len());
+    assert_ne!(instances.triggered[&0], instances.triggered[&1]);
+    assert_ne!(instances.triggered[&1], instances.triggered[&2]);
+    assert_ne!(instances.triggered[&0], instances.triggered[&2]);
+    assert_eq!(instances.triggered[&0], instances.damaged[&0]);
+    assert_eq!(instances.triggered[&1], instances.missed[&1]);
+    assert_eq!(instances.triggered[&2], instances.damaged[&2]);
+}
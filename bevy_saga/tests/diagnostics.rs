@@ -0,0 +1,91 @@
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::prelude::{App, Update};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{ErrStage, OkStage, SagaDiagnosticsPlugin, SagaStepMetric};
+use bevy_saga::saga_event;
+
+#[saga_event]
+struct Attack(u8);
+
+#[saga_event]
+struct Aimed(u8);
+
+#[saga_event]
+struct Damage;
+
+#[saga_event]
+struct Missed;
+
+fn aim(Attack(power): Attack) -> Option<Aimed> {
+    (power > 0).then_some(Aimed(power))
+}
+
+fn attack(Aimed(power): Aimed) -> Result<Damage, Missed> {
+    if power > 1 { Ok(Damage) } else { Err(Missed) }
+}
+
+fn take_damage(_: Damage) {}
+
+fn miss(_: Missed) {}
+
+fn measurement(app: &App, step: &str, metric: SagaStepMetric) -> Option<f64> {
+    let path = SagaDiagnosticsPlugin::path("attack", step, metric);
+    app.world()
+        .resource::<DiagnosticsStore>()
+        .get_measurement(&path)
+        .map(|measurement| measurement.value)
+}
+
+#[test]
+fn records_the_metrics_of_every_step() {
+    let mut app = App::new();
+    app.add_plugins(SagaDiagnosticsPlugin);
+    app.add_named_saga("attack", Update, (aim, attack.ok(take_damage).err(miss)));
+    for power in [0, 1, 2, 3] {
+        app.world_mut().send_event(Attack(power));
+    }
+    app.update();
+
+    assert_eq!(measurement(&app, "aim", SagaStepMetric::Events), Some(4.0));
+    assert_eq!(measurement(&app, "aim", SagaStepMetric::Dropped), Some(1.0));
+    assert_eq!(measurement(&app, "attack", SagaStepMetric::Events), Some(3.0));
+    assert_eq!(measurement(&app, "attack", SagaStepMetric::Ok), Some(2.0));
+    assert_eq!(measurement(&app, "attack", SagaStepMetric::Err), Some(1.0));
+    assert_eq!(measurement(&app, "take_damage", SagaStepMetric::Events), Some(2.0));
+    assert!(measurement(&app, "miss", SagaStepMetric::Time).is_some_and(|time| time >= 0.0));
+    assert_eq!(measurement(&app, "take_damage", SagaStepMetric::Err), None);
+}
+
+#[test]
+fn measures_every_frame_once_a_step_recorded_a_metric() {
+    let mut app = App::new();
+    app.add_plugins(SagaDiagnosticsPlugin);
+    app.add_named_saga("attack", Update, (aim, attack.ok(take_damage).err(miss)));
+    app.world_mut().send_event(Attack(2));
+    app.update();
+    app.update();
+
+    assert_eq!(measurement(&app, "aim", SagaStepMetric::Events), Some(0.0));
+    let path = SagaDiagnosticsPlugin::path("attack", "aim", SagaStepMetric::Events);
+    let history = app.world().resource::<DiagnosticsStore>().get(&path).unwrap().history_len();
+    assert_eq!(history, 2);
+}
+
+#[test]
+fn escapes_the_names_in_the_paths() {
+    let mut app = App::new();
+    app.add_plugins(SagaDiagnosticsPlugin);
+    app.add_named_saga("combat/attack", Update, take_damage);
+    app.add_named_saga("", Update, miss);
+    app.world_mut().send_event(Damage);
+    app.world_mut().send_event(Missed);
+    app.update();
+
+    let store = app.world().resource::<DiagnosticsStore>();
+    let path = SagaDiagnosticsPlugin::path("combat/attack", "take_damage", SagaStepMetric::Events);
+    assert_eq!(path.as_str(), "saga/combat_attack/take_damage/events");
+    assert_eq!(store.get_measurement(&path).map(|events| events.value), Some(1.0));
+    let path = SagaDiagnosticsPlugin::path("", "miss", SagaStepMetric::Events);
+    assert_eq!(path.as_str(), "saga/_/miss/events");
+    assert_eq!(store.get_measurement(&path).map(|events| events.value), Some(1.0));
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::{App, ResMut, Resource, Update};
+use bevy_saga::prelude::{SagaId, SagaInstance};
 use bevy_saga::SagaRegistry;
 use bevy_saga::saga_event;
 
@@ -64,5 +65,59 @@ fn main() {
 
     app.world_mut().send_event(Producer);
     app.update();
-    assert_ne!(3, app.world().resource::<EventsConsumed>().0);
+    assert_eq!(3, app.world().resource::<EventsConsumed>().0);
+}
+
+#[derive(Default, Resource)]
+struct Sagas(Vec<SagaId>);
+
+fn record_saga(_: Producer, instance: SagaInstance, mut sagas: ResMut<Sagas>) {
+    sagas.0.push(instance.id().saga());
+}
+
+#[test]
+fn every_saga_gets_its_own_instance() {
+    let mut app = App::new();
+    app.init_resource::<EventsConsumed>();
+    app.init_resource::<Sagas>();
+    app.add_saga(Update, ((producer1, record_saga), process1, consumer));
+    app.add_saga(Update, ((producer2, record_saga), process2, consumer));
+
+    app.world_mut().send_event(Producer);
+    app.world_mut().send_event(Producer);
+    app.update();
+    assert_eq!(4, app.world().resource::<EventsConsumed>().0);
+    let sagas = &app.world().resource::<Sagas>().0;
+    assert_eq!(4, sagas.len());
+    assert_eq!(2, sagas.iter().filter(|saga| **saga == sagas[0]).count());
+}
+
+fn forward(_: Produced1) -> Produced2 {
+    Produced2
+}
+
+fn backward(_: Produced2) -> Produced1 {
+    Produced1
+}
+
+fn consume1(_: Produced1, mut number_consumed: ResMut<EventsConsumed>) {
+    number_consumed.0 += 1;
+}
+
+fn consume2(_: Produced2, mut number_consumed: ResMut<EventsConsumed>) {
+    number_consumed.0 += 1;
+}
+
+#[test]
+fn sagas_use_the_same_events_in_opposite_orders() {
+    let mut app = App::new();
+    app.init_resource::<EventsConsumed>();
+    app.add_saga(Update, (forward, consume2));
+    app.add_saga(Update, (backward, consume1));
+
+    // Both events start an instance of both sagas.
+    app.world_mut().send_event(Produced1);
+    app.world_mut().send_event(Produced2);
+    app.update();
+    assert_eq!(4, app.world().resource::<EventsConsumed>().0);
 }
@@ -0,0 +1,121 @@
+use bevy::prelude::{App, Component, Query, ResMut, Resource, Update};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{Awaited, Correlated, Gathered, LightweightStage, await_event, gather};
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Damage(Vec<u8>);
+
+#[derive(Component)]
+struct Helmet(u8);
+
+#[derive(Component)]
+struct Chestplate(u8);
+
+#[saga_event]
+struct Hit(u8);
+
+#[saga_event]
+struct Reduction(u8);
+
+#[saga_event]
+struct Reduced(u8);
+
+fn helmet(_: Hit, helmets: Query<&Helmet>) -> Reduction {
+    Reduction(helmets.iter().map(|helmet| helmet.0).sum())
+}
+
+fn chestplate(_: Hit, chestplates: Query<&Chestplate>) -> Reduction {
+    Reduction(chestplates.iter().map(|chestplate| chestplate.0).sum())
+}
+
+fn dodge(Hit(strength): Hit) -> Reduction {
+    Reduction(strength / 2)
+}
+
+fn reduce(Gathered(reductions): Gathered<Reduction>) -> Reduced {
+    Reduced(reductions.iter().map(|reduction| reduction.0).sum())
+}
+
+fn take_damage(Reduced(reduction): Reduced, mut damage: ResMut<Damage>) {
+    damage.0.push(reduction);
+}
+
+fn order(Gathered(reductions): Gathered<Reduction>, mut damage: ResMut<Damage>) {
+    damage.0.extend(reductions.iter().map(|reduction| reduction.0));
+}
+
+#[test]
+fn collects_the_replies_of_all_branches() {
+    let mut app = App::new();
+    app.init_resource::<Damage>();
+    app.world_mut().spawn((Helmet(2), Chestplate(3)));
+    app.world_mut().spawn(Helmet(1));
+    app.add_saga(Update, (gather((helmet, chestplate, dodge)), reduce, take_damage));
+    app.world_mut().send_event(Hit(10));
+    app.world_mut().send_event(Hit(4));
+    app.update();
+    assert_eq!(app.world().resource::<Damage>().0, vec![11, 8]);
+}
+
+#[test]
+fn keeps_the_order_of_the_branches() {
+    let mut app = App::new();
+    app.init_resource::<Damage>();
+    app.world_mut().spawn((Helmet(2), Chestplate(3)));
+    app.add_saga(Update, (gather((dodge, chestplate, helmet)), order));
+    app.world_mut().send_event(Hit(10));
+    app.update();
+    assert_eq!(app.world().resource::<Damage>().0, vec![5, 3, 2]);
+}
+
+#[saga_event]
+struct Parry(u8);
+
+#[saga_event]
+struct Volley;
+
+impl Correlated for Hit {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Correlated for Parry {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+fn volley(_: Volley) -> Vec<Hit> {
+    vec![Hit(10), Hit(4)]
+}
+
+#[test]
+fn waits_for_branches_that_suspend() {
+    let mut app = App::new();
+    app.init_resource::<Damage>();
+    app.add_saga(Update, (
+        volley,
+        gather((
+            dodge,
+            await_event::<Hit, Parry>().map_event(|Awaited(_, Parry(hit))| Reduction(hit)),
+        )),
+        order,
+    ));
+    app.world_mut().send_event(Volley);
+    app.update();
+    assert!(app.world().resource::<Damage>().0.is_empty());
+
+    // The replies to every hit are gathered separately.
+    app.world_mut().send_event(Parry(4));
+    app.update();
+    assert_eq!(app.world().resource::<Damage>().0, vec![2, 4]);
+    app.world_mut().send_event(Parry(10));
+    app.update();
+    assert_eq!(app.world().resource::<Damage>().0, vec![2, 4, 5, 10]);
+}
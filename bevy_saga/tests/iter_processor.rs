@@ -0,0 +1,79 @@
+use bevy::prelude::{App, Component, Entity, Query, ResMut, Resource, Update, With};
+use bevy_saga::{SagaRegistry, prelude::Saga};
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Damaged(Vec<(Entity, u8)>);
+
+#[derive(Component)]
+struct InRange;
+
+#[saga_event]
+struct Explosion(u8);
+
+#[saga_event]
+struct Damage(Entity, u8);
+
+#[saga_event]
+struct Hit(u8);
+
+fn explode(Explosion(strength): Explosion, targets: Query<Entity, With<InRange>>) -> Vec<Damage> {
+    targets.iter().map(|target| Damage(target, strength)).collect()
+}
+
+fn shrapnel(Explosion(strength): Explosion) -> impl Iterator<Item = Hit> + use<> {
+    (1..=strength).map(Hit)
+}
+
+fn take_damage(Damage(target, damage): Damage, mut damaged: ResMut<Damaged>) {
+    damaged.0.push((target, damage));
+}
+
+fn take_hit(Hit(damage): Hit, mut damaged: ResMut<Damaged>) {
+    damaged.0.push((Entity::PLACEHOLDER, damage));
+}
+
+fn test<M>(saga: impl Saga<M>, targets: usize) -> Vec<(Entity, u8)> {
+    let mut app = App::new();
+    app.init_resource::<Damaged>();
+    for _ in 0..targets {
+        app.world_mut().spawn(InRange);
+    }
+    app.add_saga(Update, saga);
+    app.world_mut().send_event(Explosion(3));
+    app.update();
+    app.world_mut().resource_mut::<Damaged>().0.drain(..).collect()
+}
+
+#[test]
+fn test_vec() {
+    let damaged = test((explode, take_damage), 2);
+    assert_eq!(damaged.len(), 2);
+    assert!(damaged.iter().all(|(_, damage)| *damage == 3));
+    assert_ne!(damaged[0].0, damaged[1].0);
+}
+
+#[test]
+fn test_empty() {
+    assert!(test((explode, take_damage), 0).is_empty());
+}
+
+#[test]
+fn test_iterator() {
+    let damaged = test((shrapnel, take_hit), 0);
+    let mut hits: Vec<_> = damaged.into_iter().map(|(_, damage)| damage).collect();
+    hits.sort();
+    assert_eq!(hits, vec![1, 2, 3]);
+}
+
+fn flurry(Explosion(strength): Explosion) -> [Hit; 2] {
+    [Hit(strength), Hit(strength + 1)]
+}
+
+#[test]
+fn test_into_iterator() {
+    let damaged = test((flurry, take_hit), 0);
+    let mut hits: Vec<_> = damaged.into_iter().map(|(_, damage)| damage).collect();
+    hits.sort();
+    assert_eq!(hits, vec![3, 4]);
+}
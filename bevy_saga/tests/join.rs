@@ -0,0 +1,179 @@
+use bevy::prelude::{App, ResMut, Resource, Trigger, Update};
+use bevy::time::{Time, Virtual};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{
+    Correlated, Joined, PendingJoins, SagaCompleted, SagaStarted, TimedOut, TimeoutStage, join,
+};
+use bevy_saga::saga_event;
+use std::time::Duration;
+
+#[derive(Default, Resource)]
+struct Engaged(Vec<(u8, u8, u8)>);
+
+#[saga_event]
+struct PathFound(u8, u8);
+
+#[saga_event]
+struct TargetLocked(u8, u8);
+
+#[saga_event]
+struct Engage(u8, u8, u8);
+
+#[saga_event]
+struct Cleared(u8);
+
+impl Correlated for PathFound {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Correlated for TargetLocked {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Correlated for Cleared {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+fn engage(Joined((path, target)): Joined<(PathFound, TargetLocked)>) -> Engage {
+    Engage(path.0, path.1, target.1)
+}
+
+fn attack(Engage(entity, path, target): Engage, mut engaged: ResMut<Engaged>) {
+    engaged.0.push((entity, path, target));
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.init_resource::<Engaged>();
+    app.add_saga(Update, (join::<(PathFound, TargetLocked)>(), engage, attack));
+    app
+}
+
+#[test]
+fn joins_events_with_the_same_key_across_updates() {
+    let mut app = app();
+    app.world_mut().send_event(TargetLocked(1, 10));
+    app.world_mut().send_event(PathFound(2, 20));
+    app.update();
+    assert!(app.world().resource::<Engaged>().0.is_empty());
+    assert_eq!(app.world().resource::<PendingJoins<(PathFound, TargetLocked)>>().len(), 2);
+
+    app.world_mut().send_event(PathFound(1, 11));
+    app.update();
+    assert_eq!(app.world().resource::<Engaged>().0, vec![(1, 11, 10)]);
+    assert!(app.world().resource::<PendingJoins<(PathFound, TargetLocked)>>().contains_key(&2));
+    assert!(!app.world().resource::<PendingJoins<(PathFound, TargetLocked)>>().contains_key(&1));
+}
+
+#[test]
+fn joins_repeated_events_in_arrival_order() {
+    let mut app = app();
+    app.world_mut().send_event(PathFound(1, 11));
+    app.world_mut().send_event(PathFound(1, 12));
+    app.world_mut().send_event(TargetLocked(1, 10));
+    app.update();
+    app.world_mut().send_event(TargetLocked(1, 20));
+    app.update();
+    assert_eq!(app.world().resource::<Engaged>().0, vec![(1, 11, 10), (1, 12, 20)]);
+    assert!(app.world().resource::<PendingJoins<(PathFound, TargetLocked)>>().is_empty());
+}
+
+#[derive(Default, Resource)]
+struct Lifecycle {
+    started: usize,
+    completed: usize,
+}
+
+#[test]
+fn only_the_first_event_starts_a_saga_instance() {
+    let mut app = app();
+    app.init_resource::<Lifecycle>();
+    app.add_observer(|_: Trigger<SagaStarted>, mut lifecycle: ResMut<Lifecycle>| {
+        lifecycle.started += 1;
+    });
+    app.add_observer(|_: Trigger<SagaCompleted>, mut lifecycle: ResMut<Lifecycle>| {
+        lifecycle.completed += 1;
+    });
+    app.world_mut().send_event(TargetLocked(1, 10));
+    app.update();
+    app.world_mut().send_event(PathFound(1, 11));
+    app.update();
+
+    assert_eq!(app.world().resource::<Engaged>().0, vec![(1, 11, 10)]);
+    let lifecycle = app.world().resource::<Lifecycle>();
+    assert_eq!(lifecycle.started, 1);
+    assert_eq!(lifecycle.completed, 1);
+}
+
+#[derive(Default, Resource)]
+struct Aborted(Vec<u8>);
+
+fn abort(TimedOut(PathFound(entity, _)): TimedOut<PathFound>, mut aborted: ResMut<Aborted>) {
+    aborted.0.push(entity);
+}
+
+fn engage_cleared(_: Joined<(PathFound, TargetLocked, Cleared)>) {}
+
+fn advance(app: &mut App, seconds: u64) {
+    app.world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .advance_by(Duration::from_secs(seconds));
+    app.update();
+}
+
+#[test]
+fn the_deadline_starts_when_any_event_arrives() {
+    let mut app = App::new();
+    app.init_resource::<Aborted>();
+    app.add_saga(Update, (
+        join::<(PathFound, TargetLocked, Cleared)>().timeout(Duration::from_secs(5), abort),
+        engage_cleared,
+    ));
+    app.world_mut().send_event(TargetLocked(1, 10));
+    app.update();
+    app.world_mut().send_event(PathFound(1, 11));
+    advance(&mut app, 3);
+    assert!(app.world().resource::<Aborted>().0.is_empty());
+
+    // Five seconds after the target was locked, but only three after the path was found.
+    advance(&mut app, 3);
+    assert_eq!(app.world().resource::<Aborted>().0, vec![1]);
+    let pending = app.world().resource::<PendingJoins<(PathFound, TargetLocked, Cleared)>>();
+    // The target waited as long as the timeout, so it expired as well.
+    assert!(pending.is_empty());
+}
+
+#[test]
+fn events_without_a_first_event_expire() {
+    let mut app = App::new();
+    app.init_resource::<Aborted>();
+    app.add_saga(Update, (
+        join::<(PathFound, TargetLocked, Cleared)>().timeout(Duration::from_secs(5), abort),
+        engage_cleared,
+    ));
+    app.world_mut().send_event(TargetLocked(1, 10));
+    app.update();
+    app.world_mut().send_event(Cleared(1));
+    advance(&mut app, 3);
+    // The target was locked five seconds ago, the clearing only two seconds ago.
+    advance(&mut app, 2);
+    let pending = app.world().resource::<PendingJoins<(PathFound, TargetLocked, Cleared)>>();
+    assert_eq!(pending.len(), 1);
+
+    advance(&mut app, 3);
+    let pending = app.world().resource::<PendingJoins<(PathFound, TargetLocked, Cleared)>>();
+    assert!(pending.is_empty());
+    assert!(app.world().resource::<Aborted>().0.is_empty());
+}
@@ -0,0 +1,79 @@
+use bevy::prelude::{App, Event, Events, ResMut, Resource, Trigger, Update};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{
+    ErrStage, OkStage, SagaCompleted, SagaFailed, SagaStarted, SagaStepCompleted,
+};
+use bevy_saga::saga_event;
+
+#[saga_event]
+struct Attack(u8);
+
+#[saga_event]
+struct Damage;
+
+#[saga_event]
+struct Missed;
+
+fn attack(Attack(power): Attack) -> Result<Damage, Missed> {
+    if power > 0 { Ok(Damage) } else { Err(Missed) }
+}
+
+fn take_damage(_: Damage) {}
+
+fn miss(_: Missed) {}
+
+fn drain<E>(app: &mut App) -> Vec<E>
+where
+    E: Event,
+{
+    app.world_mut().resource_mut::<Events<E>>().drain().collect()
+}
+
+#[test]
+fn reports_the_steps_of_a_completed_instance() {
+    let mut app = App::new();
+    app.add_saga(Update, attack.ok(take_damage).err(miss));
+    app.world_mut().send_event(Attack(3));
+    app.update();
+
+    let started = drain::<SagaStarted>(&mut app);
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].name.as_ref(), "saga 0");
+    assert!(started[0].event.ends_with("Attack"));
+    let instance = started[0].instance;
+
+    let steps: Vec<_> = drain::<SagaStepCompleted>(&mut app)
+        .into_iter()
+        .inspect(|step| assert_eq!(step.instance, instance))
+        .map(|step| step.step.to_string())
+        .collect();
+    assert_eq!(steps, vec!["attack", "take_damage"]);
+
+    let completed = drain::<SagaCompleted>(&mut app);
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].instance, instance);
+    assert!(drain::<SagaFailed>(&mut app).is_empty());
+}
+
+#[derive(Default, Resource)]
+struct Outcomes(Vec<String>);
+
+#[test]
+fn triggers_observers_when_an_instance_fails() {
+    let mut app = App::new();
+    app.init_resource::<Outcomes>();
+    app.add_saga(Update, attack.ok(take_damage).err(miss));
+    app.add_observer(|failed: Trigger<SagaFailed>, mut outcomes: ResMut<Outcomes>| {
+        outcomes.0.push(format!("failed {}", failed.error.rsplit("::").next().unwrap()));
+    });
+    app.add_observer(|completed: Trigger<SagaCompleted>, mut outcomes: ResMut<Outcomes>| {
+        outcomes.0.push(format!("completed {}", completed.instance));
+    });
+    app.world_mut().send_event(Attack(0));
+    app.update();
+
+    assert_eq!(
+        app.world().resource::<Outcomes>().0,
+        vec!["failed Missed", "completed saga#0"],
+    );
+}
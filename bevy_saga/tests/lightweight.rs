@@ -0,0 +1,76 @@
+use bevy::prelude::{App, ResMut, Resource, Trigger, Update};
+use bevy_saga::prelude::{LightweightStage, SagaStepCompleted};
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Hits(Vec<u8>);
+
+/// Counts the events that were inspected.
+#[derive(Default, Resource)]
+struct Inspected(u8);
+
+#[saga_event]
+struct Attack(u8);
+
+#[saga_event]
+struct Damage(u8);
+
+#[saga_event]
+struct Hit(u8);
+
+fn attack(Attack(strength): Attack) -> Damage {
+    Damage(strength)
+}
+
+fn take_damage(Damage(damage): Damage, mut hits: ResMut<Hits>) {
+    hits.0.push(damage);
+}
+
+fn take_hit(Hit(hit): Hit, mut hits: ResMut<Hits>) {
+    hits.0.push(hit);
+}
+
+fn run(app: &mut App, attacks: &[u8]) -> Vec<u8> {
+    for strength in attacks {
+        app.world_mut().send_event(Attack(*strength));
+    }
+    app.update();
+    app.update();
+    app.world().resource::<Hits>().0.clone()
+}
+
+#[test]
+fn lightweight_steps_map_filter_and_inspect() {
+    let mut app = App::new();
+    app.init_resource::<Hits>();
+    app.init_resource::<Inspected>();
+    app.add_saga(
+        Update,
+        (
+            attack
+                .filter_event(|Damage(damage)| *damage > 0)
+                .inspect_event(|Damage(damage)| assert_ne!(*damage, 0))
+                .map_event(|Damage(damage)| Hit(damage * 10)),
+            take_hit,
+        ),
+    );
+    app.add_observer(|step: Trigger<SagaStepCompleted>, mut inspected: ResMut<Inspected>| {
+        if &*step.step == "inspect_event" {
+            inspected.0 += 1;
+        }
+    });
+    assert_eq!(vec![10, 30], run(&mut app, &[1, 0, 3]));
+    assert_eq!(2, app.world().resource::<Inspected>().0);
+}
+
+#[test]
+fn lightweight_steps_keep_the_event_type() {
+    let mut app = App::new();
+    app.init_resource::<Hits>();
+    app.add_saga(
+        Update,
+        (attack.map_event(|Damage(damage)| Damage(damage * 2)), take_damage),
+    );
+    assert_eq!(vec![2, 6], run(&mut app, &[1, 3]));
+}
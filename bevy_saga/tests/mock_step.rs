@@ -0,0 +1,59 @@
+use bevy::prelude::{App, Entity, Query, Update};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{MockStep, SagaTestApp};
+use bevy_saga::saga_event;
+
+#[saga_event]
+struct AttackTrigger(Entity);
+
+#[saga_event]
+struct Offense(u8);
+
+#[saga_event]
+struct Damage(u8);
+
+fn calculate_offense(AttackTrigger(attacker): AttackTrigger, _: Query<Entity>) -> Offense {
+    Offense(attacker.index() as u8)
+}
+
+fn calculate_damage(Offense(offense): Offense) -> Damage {
+    Damage(offense * 2)
+}
+
+fn take_damage(_: Damage) {}
+
+fn take_offense(_: Offense) {}
+
+#[test]
+fn replaces_the_step_with_the_mock() {
+    let mut app = SagaTestApp::new();
+    let saga = app.add_saga(Update, (calculate_offense, calculate_damage, take_damage));
+    app.mock_step::<AttackTrigger, Offense>(saga, |_| Offense(5));
+    app.send(AttackTrigger(Entity::PLACEHOLDER)).update();
+
+    app.assert_emitted::<Offense>(|offense| offense.0 == 5);
+    app.assert_emitted::<Damage>(|damage| damage.0 == 10);
+    app.assert_path(["calculate_offense", "calculate_damage", "take_damage"]);
+}
+
+#[test]
+#[should_panic(expected = "No processor of")]
+fn fails_when_no_step_produces_the_output() {
+    let mut app = App::new();
+    let saga = app.add_saga(Update, (calculate_offense, calculate_damage, take_damage));
+    app.mock_step::<AttackTrigger, Damage>(saga, |_| Damage(1));
+}
+
+#[test]
+fn only_replaces_the_step_in_the_saga() {
+    let mut app = SagaTestApp::new();
+    let saga = app.add_saga(Update, (calculate_offense, calculate_damage, take_damage));
+    app.add_saga(Update, (calculate_offense, take_offense));
+    app.mock_step::<AttackTrigger, Offense>(saga, |_| Offense(5));
+    app.send(AttackTrigger(Entity::from_raw(3))).update();
+
+    let mut offenses: Vec<_> = app.emitted::<Offense>().iter().map(|offense| offense.0).collect();
+    offenses.sort();
+    assert_eq!(offenses, vec![3, 5]);
+    app.assert_emitted::<Damage>(|damage| damage.0 == 10);
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::{App, Entity, Events, Query, ResMut, Resource, Update};
+use bevy_saga::prelude::{Awaited, Correlated, PendingAwaits, SagaCancelled, await_event};
+use bevy_saga::{SagaEvent, SagaRegistry};
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Hits(Vec<Entity>);
+
+#[saga_event]
+struct AttackTrigger {
+    #[owner]
+    by: Entity,
+    target: u8,
+}
+
+#[saga_event]
+struct Aim(Entity, u8);
+
+#[saga_event]
+struct TargetLocked(u8);
+
+#[saga_event]
+struct Fire(#[owner] Option<Entity>);
+
+impl Correlated for Aim {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.1
+    }
+}
+
+impl Correlated for TargetLocked {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+fn aim(AttackTrigger { by, target }: AttackTrigger) -> Aim {
+    Aim(by, target)
+}
+
+fn hit(
+    Awaited(Aim(by, _), _): Awaited<Aim, TargetLocked>,
+    attackers: Query<Entity>,
+    mut hits: ResMut<Hits>,
+) {
+    hits.0.push(attackers.get(by).unwrap());
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.init_resource::<Hits>();
+    app.add_saga(Update, (aim, await_event::<Aim, TargetLocked>(), hit));
+    app
+}
+
+fn cancelled(app: &mut App) -> usize {
+    app.world_mut().resource_mut::<Events<SagaCancelled>>().drain().count()
+}
+
+#[test]
+fn the_owner_field_is_the_owner_of_the_event() {
+    let entity = Entity::from_raw(3);
+    let trigger = AttackTrigger { by: entity, target: 0 };
+    assert_eq!(trigger.owner(), Some(entity));
+    assert_eq!(Fire(Some(entity)).owner(), Some(entity));
+    assert_eq!(Fire(None).owner(), None);
+    assert_eq!(TargetLocked(0).owner(), None);
+}
+
+#[test]
+fn despawning_the_owner_cancels_its_saga_instances() {
+    let mut app = app();
+    let despawned = app.world_mut().spawn_empty().id();
+    let kept = app.world_mut().spawn_empty().id();
+    app.world_mut().send_event(AttackTrigger { by: despawned, target: 1 });
+    app.world_mut().send_event(AttackTrigger { by: kept, target: 1 });
+    app.update();
+    assert_eq!(app.world().resource::<PendingAwaits<Aim, TargetLocked>>().len(), 2);
+
+    app.world_mut().despawn(despawned);
+    assert_eq!(cancelled(&mut app), 1);
+    assert_eq!(app.world().resource::<PendingAwaits<Aim, TargetLocked>>().len(), 1);
+
+    app.world_mut().send_event(TargetLocked(1));
+    app.update();
+    assert_eq!(app.world().resource::<Hits>().0, vec![kept]);
+}
+
+#[test]
+fn saga_instances_of_despawned_owners_are_cancelled_when_they_start() {
+    let mut app = app();
+    let despawned = app.world_mut().spawn_empty().id();
+    app.world_mut().despawn(despawned);
+    app.world_mut().send_event(AttackTrigger { by: despawned, target: 1 });
+    app.update();
+    assert!(app.world().resource::<PendingAwaits<Aim, TargetLocked>>().is_empty());
+    assert_eq!(cancelled(&mut app), 1);
+}
@@ -0,0 +1,211 @@
+use bevy::prelude::{App, ResMut, Resource, Trigger, Update};
+use bevy::time::{Time, Virtual};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{
+    Awaited, Correlated, LightweightStage, SagaCompleted, SagaInstance, SagaInstanceId,
+    TimedOut, TimeoutStage, await_event, race,
+};
+use bevy_saga::saga_event;
+use std::time::Duration;
+
+#[derive(Default, Resource)]
+struct Moves(Vec<Move>);
+
+#[derive(Default, Resource)]
+struct Instances {
+    moved: Vec<SagaInstanceId>,
+    completed: Vec<SagaInstanceId>,
+}
+
+#[saga_event]
+struct Turn(u8);
+
+#[saga_event]
+struct PlayerInput(u8);
+
+#[saga_event]
+struct AiDecision(u8);
+
+#[saga_event]
+#[derive(Debug, PartialEq)]
+enum Move {
+    Player,
+    Ai,
+    Skipped,
+}
+
+impl Correlated for Turn {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Correlated for PlayerInput {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Correlated for AiDecision {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+fn player(_: Turn) -> Move {
+    Move::Player
+}
+
+fn ai(_: Turn) -> Move {
+    Move::Ai
+}
+
+fn make_move(event: Move, mut moves: ResMut<Moves>) {
+    moves.0.push(event);
+}
+
+fn make_tracked_move(
+    event: Move,
+    instance: SagaInstance,
+    mut moves: ResMut<Moves>,
+    mut instances: ResMut<Instances>,
+) {
+    moves.0.push(event);
+    instances.moved.push(instance.id());
+}
+
+fn skip_turn(_: TimedOut<Turn>, mut moves: ResMut<Moves>) {
+    moves.0.push(Move::Skipped);
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.init_resource::<Moves>();
+    app.add_saga(Update, (
+        race((
+            await_event::<Turn, PlayerInput>().map_event(|_: Awaited<Turn, PlayerInput>| Move::Player),
+            await_event::<Turn, AiDecision>().map_event(|_: Awaited<Turn, AiDecision>| Move::Ai),
+        )),
+        make_move,
+    ));
+    app
+}
+
+#[test]
+fn the_first_branch_to_answer_wins() {
+    let mut app = App::new();
+    app.init_resource::<Moves>();
+    app.init_resource::<Instances>();
+    app.add_saga(Update, (
+        race((
+            await_event::<Turn, PlayerInput>()
+                .timeout(Duration::from_secs(5), skip_turn)
+                .map_event(|_: Awaited<Turn, PlayerInput>| Move::Player),
+            await_event::<Turn, AiDecision>()
+                .timeout(Duration::from_secs(5), skip_turn)
+                .map_event(|_: Awaited<Turn, AiDecision>| Move::Ai),
+        )),
+        make_tracked_move,
+    ));
+    app.add_observer(|completed: Trigger<SagaCompleted>, mut instances: ResMut<Instances>| {
+        instances.completed.push(completed.instance);
+    });
+    app.world_mut().send_event(Turn(1));
+    app.world_mut().send_event(Turn(2));
+    app.update();
+    app.world_mut().send_event(AiDecision(1));
+    app.update();
+    app.world_mut().send_event(PlayerInput(2));
+    app.update();
+    assert_eq!(app.world().resource::<Moves>().0, vec![Move::Ai, Move::Player]);
+    let instances = app.world().resource::<Instances>();
+    assert_eq!(instances.moved.len(), 2);
+    assert_eq!(instances.completed.len(), 2);
+    assert!(instances.moved.iter().all(|moved| instances.completed.contains(moved)));
+
+    // The losing branches stopped waiting, so their timeouts don't fire.
+    app.world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .advance_by(Duration::from_secs(10));
+    app.update();
+    assert_eq!(app.world().resource::<Moves>().0, vec![Move::Ai, Move::Player]);
+}
+
+#[test]
+fn the_outputs_of_losing_branches_are_discarded() {
+    let mut app = app();
+    app.world_mut().send_event(Turn(1));
+    app.update();
+    app.world_mut().send_event(PlayerInput(1));
+    app.update();
+    app.world_mut().send_event(AiDecision(1));
+    app.update();
+    assert_eq!(app.world().resource::<Moves>().0, vec![Move::Player]);
+}
+
+#[test]
+fn branches_that_answer_in_the_same_update_produce_one_output() {
+    let mut app = App::new();
+    app.init_resource::<Moves>();
+    app.add_saga(Update, (race((player, ai)), make_move));
+    app.world_mut().send_event(Turn(1));
+    app.world_mut().send_event(Turn(2));
+    app.update();
+    assert_eq!(app.world().resource::<Moves>().0, vec![Move::Player, Move::Player]);
+}
+
+#[saga_event]
+struct Round;
+
+fn turns(_: Round) -> Vec<Turn> {
+    vec![Turn(1), Turn(2)]
+}
+
+#[test]
+fn every_event_of_a_saga_instance_is_raced() {
+    let mut app = App::new();
+    app.init_resource::<Moves>();
+    app.init_resource::<Instances>();
+    app.add_saga(Update, (
+        turns,
+        race((
+            await_event::<Turn, PlayerInput>().map_event(|_: Awaited<Turn, PlayerInput>| Move::Player),
+            await_event::<Turn, AiDecision>().map_event(|_: Awaited<Turn, AiDecision>| Move::Ai),
+        )),
+        make_tracked_move,
+    ));
+    app.add_observer(|completed: Trigger<SagaCompleted>, mut instances: ResMut<Instances>| {
+        instances.completed.push(completed.instance);
+    });
+    app.world_mut().send_event(Round);
+    app.update();
+    app.world_mut().send_event(PlayerInput(1));
+    app.update();
+    assert_eq!(app.world().resource::<Moves>().0, vec![Move::Player]);
+    assert!(app.world().resource::<Instances>().completed.is_empty());
+
+    // The second turn is still raced, and completes the saga instance.
+    app.world_mut().send_event(AiDecision(1));
+    app.world_mut().send_event(AiDecision(2));
+    app.update();
+    assert_eq!(app.world().resource::<Moves>().0, vec![Move::Player, Move::Ai]);
+    let instances = app.world().resource::<Instances>();
+    assert_eq!(instances.moved[0], instances.moved[1]);
+    assert_eq!(instances.completed, vec![instances.moved[0]]);
+}
+
+#[test]
+fn branches_race_every_event_of_a_saga_instance_in_the_same_update() {
+    let mut app = App::new();
+    app.init_resource::<Moves>();
+    app.add_saga(Update, (turns, race((player, ai)), make_move));
+    app.world_mut().send_event(Round);
+    app.update();
+    assert_eq!(app.world().resource::<Moves>().0, vec![Move::Player, Move::Player]);
+}
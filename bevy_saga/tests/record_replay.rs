@@ -0,0 +1,104 @@
+use bevy::prelude::{App, Last, Res, ResMut, Resource, Update};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{SagaRecorderPlugin, SagaRecording, SagaReplay, SagaReplayPlugin};
+use bevy_saga::saga_event;
+
+#[saga_event(serde)]
+struct Attack {
+    power: u8,
+}
+
+#[saga_event(serde)]
+struct Damage(u8);
+
+#[saga_event]
+struct Heal;
+
+/// The frames in which the damage was taken, and the damage.
+#[derive(Resource, Default, Debug, PartialEq)]
+struct Log(Vec<(u8, u8)>);
+
+#[derive(Resource, Default)]
+struct Frame(u8);
+
+fn attack(Attack { power }: Attack) -> Damage {
+    Damage(power * 2)
+}
+
+fn take_damage(Damage(damage): Damage, mut log: ResMut<Log>, frame: Res<Frame>) {
+    log.0.push((frame.0, damage));
+}
+
+fn heal(_: Heal) {}
+
+fn count_frames(mut frame: ResMut<Frame>) {
+    frame.0 += 1;
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.init_resource::<Log>();
+    app.init_resource::<Frame>();
+    app.add_systems(Last, count_frames);
+    app.add_saga(Update, (attack, take_damage));
+    app.add_saga(Update, heal);
+    app
+}
+
+#[test]
+fn records_the_events_that_enter_the_sagas() {
+    let mut app = app();
+    app.add_plugins(SagaRecorderPlugin);
+    app.world_mut().send_event(Attack { power: 1 });
+    app.update();
+    app.update();
+    app.world_mut().send_event(Attack { power: 2 });
+    app.update();
+
+    let recording = app.world().resource::<SagaRecording>();
+    let events: Vec<_> = recording
+        .events()
+        .iter()
+        .map(|recorded| (recorded.frame, recorded.value.clone()))
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            (0, serde_json::json!({ "power": 1 })),
+            (2, serde_json::json!({ "power": 2 })),
+        ]
+    );
+}
+
+#[test]
+fn replays_a_recording_in_a_fresh_app() {
+    let mut recorded = app();
+    recorded.add_plugins(SagaRecorderPlugin);
+    recorded.world_mut().send_event(Attack { power: 1 });
+    recorded.update();
+    recorded.update();
+    recorded.world_mut().send_event(Attack { power: 3 });
+    recorded.world_mut().send_event(Attack { power: 4 });
+    recorded.update();
+
+    let log = serde_json::to_string(recorded.world().resource::<SagaRecording>()).unwrap();
+    let recording: SagaRecording = serde_json::from_str(&log).unwrap();
+    let mut replayed = app();
+    replayed.add_plugins(SagaReplayPlugin::new(recording));
+    for _ in 0..3 {
+        replayed.update();
+    }
+
+    assert!(replayed.world().resource::<SagaReplay>().is_finished());
+    assert_eq!(replayed.world().resource::<Log>(), recorded.world().resource::<Log>());
+    assert_eq!(replayed.world().resource::<Log>().0, vec![(0, 2), (2, 6), (2, 8)]);
+}
+
+#[test]
+#[should_panic(expected = "entered a saga, but it can't be recorded")]
+fn fails_to_record_events_without_serde() {
+    let mut app = app();
+    app.add_plugins(SagaRecorderPlugin);
+    app.world_mut().send_event(Heal);
+    app.update();
+}
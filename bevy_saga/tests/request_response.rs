@@ -1,5 +1,6 @@
-use bevy::prelude::{App, Update, ResMut, Resource};
+use bevy::prelude::{App, EventReader, IntoScheduleConfigs, ResMut, Resource, Update};
 use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::BevySagaUtil;
 use bevy_saga::saga_event;
 
 #[derive(Default, Resource)]
@@ -38,3 +39,23 @@ fn main() {
     app.update();
     assert_eq!(app.world_mut().resource::<Counter>().0, 3)
 }
+
+fn read_responses(mut responses: EventReader<Response>, mut counter: ResMut<Counter>) {
+    for _ in responses.read() {
+        counter.0 += 2;
+    }
+}
+
+#[test]
+fn plain_systems_read_responses_that_no_saga_handles() {
+    let mut app = App::new();
+    app.init_resource::<Counter>();
+    app.add_event::<Response>();
+    let processor = app.add_event_processor(handle_request);
+    app.add_systems(Update, (processor, read_responses).chain());
+    app.world_mut().send_event(Request {
+        to: "Player".to_string(),
+    });
+    app.update();
+    assert_eq!(app.world_mut().resource::<Counter>().0, 3)
+}
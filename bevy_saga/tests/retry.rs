@@ -0,0 +1,132 @@
+use bevy::prelude::{App, ResMut, Resource, Trigger, Update};
+use bevy_saga::prelude::{
+    ErrStage, OkStage, PendingRetries, RetryPolicy, RetryStage, SagaCompleted, SagaStepCompleted,
+};
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+
+#[derive(Resource)]
+struct Forge {
+    attempts: u8,
+    succeeds_at: u8,
+    crafted: u8,
+    failed: u8,
+}
+
+impl Forge {
+    fn succeeding_at(attempt: u8) -> Self {
+        Forge {
+            attempts: 0,
+            succeeds_at: attempt,
+            crafted: 0,
+            failed: 0,
+        }
+    }
+}
+
+#[saga_event]
+struct Craft;
+
+#[saga_event]
+struct Crafted;
+
+#[saga_event]
+struct Broken;
+
+fn craft(_: Craft, mut forge: ResMut<Forge>) -> Result<Crafted, Broken> {
+    forge.attempts += 1;
+    if forge.attempts >= forge.succeeds_at {
+        Ok(Crafted)
+    } else {
+        Err(Broken)
+    }
+}
+
+fn crafted(_: Crafted, mut forge: ResMut<Forge>) {
+    forge.crafted += 1;
+}
+
+fn broken(_: Broken, mut forge: ResMut<Forge>) {
+    forge.failed += 1;
+}
+
+#[test]
+fn retries_until_ok() {
+    let mut app = App::new();
+    app.insert_resource(Forge::succeeding_at(3));
+    app.add_saga(
+        Update,
+        craft
+            .retry(RetryPolicy::times(3).backoff_frames(2))
+            .ok(crafted)
+            .err(broken),
+    );
+
+    app.world_mut().send_event(Craft);
+    app.update();
+    assert_eq!(1, app.world().resource::<Forge>().attempts);
+    assert_eq!(1, app.world().resource::<PendingRetries<Craft>>().len());
+
+    app.update();
+    assert_eq!(1, app.world().resource::<Forge>().attempts);
+
+    app.update();
+    app.update();
+    assert_eq!(2, app.world().resource::<Forge>().attempts);
+    assert_eq!(0, app.world().resource::<Forge>().crafted);
+
+    app.update();
+    let forge = app.world().resource::<Forge>();
+    assert_eq!(3, forge.attempts);
+    assert_eq!(1, forge.crafted);
+    assert_eq!(0, forge.failed);
+    assert!(app.world().resource::<PendingRetries<Craft>>().is_empty());
+}
+
+#[test]
+fn err_is_sent_when_retries_run_out() {
+    let mut app = App::new();
+    app.insert_resource(Forge::succeeding_at(u8::MAX));
+    app.add_saga(Update, craft.retry(RetryPolicy::times(2)).ok(crafted).err(broken));
+
+    app.world_mut().send_event(Craft);
+    app.update();
+    app.update();
+    assert_eq!(2, app.world().resource::<Forge>().attempts);
+    assert_eq!(0, app.world().resource::<Forge>().failed);
+
+    app.update();
+    let forge = app.world().resource::<Forge>();
+    assert_eq!(3, forge.attempts);
+    assert_eq!(1, forge.failed);
+    assert!(app.world().resource::<PendingRetries<Craft>>().is_empty());
+
+    app.update();
+    assert_eq!(3, app.world().resource::<Forge>().attempts);
+}
+
+#[derive(Default, Resource)]
+struct Lifecycle(Vec<String>);
+
+#[test]
+fn every_attempt_completes_the_step() {
+    let mut app = App::new();
+    app.insert_resource(Forge::succeeding_at(3));
+    app.init_resource::<Lifecycle>();
+    app.add_saga(Update, craft.retry(RetryPolicy::times(3)).ok(crafted).err(broken));
+    app.add_observer(|step: Trigger<SagaStepCompleted>, mut lifecycle: ResMut<Lifecycle>| {
+        lifecycle.0.push(step.step.to_string());
+    });
+    app.add_observer(|_: Trigger<SagaCompleted>, mut lifecycle: ResMut<Lifecycle>| {
+        lifecycle.0.push("completed".to_string());
+    });
+
+    app.world_mut().send_event(Craft);
+    app.update();
+    app.update();
+    app.update();
+    assert_eq!(
+        app.world().resource::<Lifecycle>().0,
+        vec!["craft", "craft", "craft", "crafted", "completed"],
+    );
+}
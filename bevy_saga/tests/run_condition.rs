@@ -0,0 +1,109 @@
+use bevy::prelude::{App, Res, ResMut, Resource, Update};
+use bevy_saga::prelude::{ConditionPolicy, HeldEvents, RunIfStage};
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Default, Resource)]
+struct Paused(bool);
+
+#[derive(Default, Resource)]
+struct Counter {
+    attacks: u8,
+    hits: u8,
+}
+
+#[saga_event]
+struct Attack;
+
+#[saga_event]
+struct Damage;
+
+fn attack(_: Attack, mut counter: ResMut<Counter>) -> Damage {
+    counter.attacks += 1;
+    Damage
+}
+
+fn take_damage(_: Damage, mut counter: ResMut<Counter>) {
+    counter.hits += 1;
+}
+
+fn running(paused: Res<Paused>) -> bool {
+    !paused.0
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.init_resource::<Paused>();
+    app.init_resource::<Counter>();
+    app
+}
+
+fn attack_once(app: &mut App) -> (u8, u8) {
+    app.world_mut().send_event(Attack);
+    app.update();
+    counter(app)
+}
+
+fn counter(app: &App) -> (u8, u8) {
+    let counter = app.world().resource::<Counter>();
+    (counter.attacks, counter.hits)
+}
+
+#[test]
+fn unmet_conditions_consume_events() {
+    let mut app = app();
+    app.add_saga(Update, (attack, take_damage.saga_run_if(running)));
+    assert_eq!((1, 1), attack_once(&mut app));
+    app.insert_resource(Paused(true));
+    assert_eq!((2, 1), attack_once(&mut app));
+    app.insert_resource(Paused(false));
+    app.update();
+    assert_eq!((2, 1), counter(&app));
+}
+
+#[test]
+fn unmet_conditions_keep_events() {
+    let mut app = app();
+    app.add_saga(
+        Update,
+        (attack, take_damage.saga_run_if_with(running, ConditionPolicy::Keep)),
+    );
+    app.insert_resource(Paused(true));
+    assert_eq!((1, 0), attack_once(&mut app));
+    app.update();
+    assert_eq!(1, app.world().resource::<HeldEvents<Damage>>().len());
+    app.insert_resource(Paused(false));
+    app.update();
+    assert_eq!((1, 1), counter(&app));
+    assert!(app.world().resource::<HeldEvents<Damage>>().is_empty());
+}
+
+#[test]
+fn saga_conditions_apply_to_all_steps() {
+    let mut app = app();
+    app.add_saga(Update, (attack, take_damage).saga_run_if(running));
+    app.insert_resource(Paused(true));
+    assert_eq!((0, 0), attack_once(&mut app));
+    app.insert_resource(Paused(false));
+    assert_eq!((1, 1), attack_once(&mut app));
+}
+
+#[test]
+fn conditions_are_checked_once_per_event_type_and_update() {
+    let mut app = app();
+    let checks = Arc::new(AtomicU8::new(0));
+    let counted = checks.clone();
+    app.add_saga(Update, (attack, take_damage).saga_run_if(move || {
+        counted.fetch_add(1, Ordering::Relaxed);
+        true
+    }));
+    app.world_mut().send_event(Attack);
+    app.world_mut().send_event(Attack);
+    app.world_mut().send_event(Attack);
+    app.update();
+    assert_eq!((3, 3), counter(&app));
+    // Once for the attacks and once for the damage.
+    assert_eq!(2, checks.load(Ordering::Relaxed));
+}
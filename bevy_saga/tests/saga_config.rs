@@ -0,0 +1,100 @@
+use bevy::ecs::error::{BevyError, ErrorContext};
+use bevy::prelude::{
+    App, Events, IntoScheduleConfigs, Res, ResMut, Resource, SystemSet, Update, World,
+};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{SagaConfig, SagaGraph, SagaStarted};
+use bevy_saga::saga_event;
+
+#[saga_event]
+struct Attack;
+
+#[saga_event]
+struct Damage;
+
+#[derive(Default, Resource)]
+struct Taken(u8);
+
+#[derive(Resource)]
+struct Armor;
+
+fn attack(_: Attack) -> Damage {
+    Damage
+}
+
+fn take_damage(_: Damage, mut taken: ResMut<Taken>) {
+    taken.0 += 1;
+}
+
+fn armored_damage(_: Damage, _: Res<Armor>) {}
+
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+struct CombatSet;
+
+#[test]
+fn names_the_saga_in_graph_exports_and_lifecycle_events() {
+    let mut app = App::new();
+    app.init_resource::<Taken>();
+    app.add_named_saga("attack", Update, (attack, take_damage));
+    app.world_mut().send_event(Attack);
+    app.update();
+
+    let graph = app.world().resource::<SagaGraph>();
+    assert!(graph.to_mermaid().contains("subgraph saga_0 [\"attack\"]"));
+    assert!(graph.to_dot().contains("label=\"attack\";"));
+    let started: Vec<_> = app
+        .world_mut()
+        .resource_mut::<Events<SagaStarted>>()
+        .drain()
+        .map(|started| started.name.to_string())
+        .collect();
+    assert_eq!(started, vec!["attack"]);
+}
+
+#[test]
+fn applies_the_system_set_and_run_condition_of_the_config() {
+    let mut app = App::new();
+    app.init_resource::<Taken>();
+    app.configure_sets(Update, CombatSet.run_if(|taken: Res<Taken>| taken.0 < 2));
+    app.add_configured_saga(
+        SagaConfig::new(Update)
+            .in_set(CombatSet)
+            .run_if(|world: &World| !world.contains_resource::<Armor>()),
+        (attack, take_damage),
+    );
+
+    app.world_mut().send_event(Attack);
+    app.update();
+    assert_eq!(app.world().resource::<Taken>().0, 1);
+
+    // The run condition of the saga consumes the event.
+    app.insert_resource(Armor);
+    app.world_mut().send_event(Attack);
+    app.update();
+    assert_eq!(app.world().resource::<Taken>().0, 1);
+
+    // The run condition of the set stops the systems of the saga.
+    app.world_mut().remove_resource::<Armor>();
+    app.world_mut().resource_mut::<Taken>().0 = 2;
+    app.world_mut().send_event(Attack);
+    app.update();
+    assert_eq!(app.world().resource::<Taken>().0, 2);
+}
+
+/// Error handlers can't access the world, so the handler reports the error by panicking with a
+/// message that Bevy's own error handler doesn't produce.
+fn report_error(_: BevyError, context: ErrorContext) {
+    panic!("The saga handler received the error of {}.", context.name());
+}
+
+#[test]
+#[should_panic(expected = "The saga handler received the error of armored_damage of attack.")]
+fn passes_the_errors_of_steps_to_the_error_handler() {
+    let mut app = App::new();
+    app.add_configured_saga(
+        SagaConfig::new(Update).named("attack").on_error(report_error),
+        (attack, armored_damage),
+    );
+    app.world_mut().send_event(Attack);
+    app.update();
+}
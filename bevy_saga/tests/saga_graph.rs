@@ -0,0 +1,75 @@
+use bevy::prelude::{App, Update};
+use bevy_saga::prelude::{ErrStage, OkStage, SagaGraph};
+use bevy_saga::SagaRegistry;
+use bevy_saga::{saga_event, saga_router};
+
+#[saga_router]
+pub enum Route {
+    Left(Left),
+    Right(Right),
+}
+
+#[saga_event]
+struct Input;
+#[saga_event]
+struct Checked;
+#[saga_event]
+struct Failed;
+#[saga_event]
+pub struct Left;
+#[saga_event]
+pub struct Right;
+
+fn check(_: Input) -> Result<Checked, Failed> {
+    Ok(Checked)
+}
+
+fn audit(_: Input) {}
+
+fn fail(_: Failed) {}
+
+fn route(_: Checked) -> Route {
+    Route::Right(Right)
+}
+
+fn left(_: Left) {}
+
+fn right(_: Right) {}
+
+fn graph() -> App {
+    let mut app = App::new();
+    app.add_saga(
+        Update,
+        (check, audit).ok(route.left(left).right(right)).err(fail),
+    );
+    app
+}
+
+#[test]
+fn saga_graph_to_dot() {
+    let app = graph();
+    let dot = app.world().resource::<SagaGraph>().to_dot();
+    assert!(dot.starts_with("digraph sagas {\n"));
+    assert!(dot.contains("event_0 [label=\"Input\", shape=ellipse];"));
+    assert!(dot.contains("subgraph cluster_saga_"));
+    assert!(dot.contains("[label=\"check\", shape=box];"));
+    assert!(dot.contains("[label=\"ok\"];"));
+    assert!(dot.contains("[label=\"err\"];"));
+    assert!(dot.contains("[label=\"sibling\"];"));
+    assert!(dot.contains("[label=\"Left\"];"));
+    assert!(dot.contains("[label=\"Right\"];"));
+}
+
+#[test]
+fn saga_graph_to_mermaid() {
+    let app = graph();
+    let graph = app.world().resource::<SagaGraph>();
+    assert_eq!(6, graph.steps().count());
+    assert_eq!(5, graph.events().len());
+    let mermaid = graph.to_mermaid();
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    assert!(mermaid.contains("([\"Checked\"])"));
+    assert!(mermaid.contains("-->|ok|"));
+    assert!(mermaid.contains("-->|err|"));
+    assert!(mermaid.contains("-->|Left|"));
+}
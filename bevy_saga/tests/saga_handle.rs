@@ -0,0 +1,156 @@
+use bevy::ecs::system::SystemIdMarker;
+use bevy::prelude::{App, Commands, Res, ResMut, Resource, Update, With};
+use bevy_saga::prelude::{
+    Awaited, CompensateStage, Correlated, ErrStage, Gathered, OkStage, RetryPolicy, RetryStage,
+    SagaControl, SagaHandle, await_event, gather,
+};
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Counter {
+    attacks: u8,
+    heals: u8,
+}
+
+#[derive(Resource)]
+struct Combat(SagaHandle);
+
+#[saga_event]
+struct Attack;
+
+#[saga_event]
+struct Damage;
+
+#[saga_event]
+struct Truce;
+
+fn attack(_: Attack) -> Damage {
+    Damage
+}
+
+fn take_damage(_: Damage, mut counter: ResMut<Counter>) {
+    counter.attacks += 1;
+}
+
+fn heal(_: Attack, mut counter: ResMut<Counter>) {
+    counter.heals += 1;
+}
+
+fn truce(_: Truce, combat: Res<Combat>, mut commands: Commands) {
+    commands.disable_saga(combat.0);
+}
+
+fn app() -> (App, SagaHandle) {
+    let mut app = App::new();
+    app.init_resource::<Counter>();
+    let combat = app.add_saga(Update, (attack, take_damage));
+    app.add_saga(Update, heal);
+    (app, combat)
+}
+
+fn attack_once(app: &mut App) -> (u8, u8) {
+    app.world_mut().send_event(Attack);
+    app.update();
+    let counter = app.world().resource::<Counter>();
+    (counter.attacks, counter.heals)
+}
+
+#[test]
+fn disabled_sagas_are_skipped() {
+    let (mut app, combat) = app();
+    assert_eq!((1, 1), attack_once(&mut app));
+    app.world_mut().disable_saga(combat);
+    assert_eq!((1, 2), attack_once(&mut app));
+    app.world_mut().enable_saga(combat);
+    assert_eq!((2, 3), attack_once(&mut app));
+}
+
+#[test]
+fn sagas_are_disabled_with_commands() {
+    let (mut app, combat) = app();
+    app.insert_resource(Combat(combat));
+    app.add_saga(Update, truce);
+    app.world_mut().send_event(Truce);
+    app.update();
+    assert_eq!((0, 1), attack_once(&mut app));
+}
+
+#[test]
+fn removed_sagas_stay_removed() {
+    let (mut app, combat) = app();
+    assert_eq!((1, 1), attack_once(&mut app));
+    app.world_mut().remove_saga(combat);
+    app.world_mut().enable_saga(combat);
+    assert_eq!((1, 2), attack_once(&mut app));
+    assert_eq!((1, 3), attack_once(&mut app));
+}
+
+impl Correlated for Attack {
+    type Key = ();
+
+    fn correlation_key(&self) {}
+}
+
+impl Correlated for Truce {
+    type Key = ();
+
+    fn correlation_key(&self) {}
+}
+
+fn make_peace(_: Awaited<Attack, Truce>, mut counter: ResMut<Counter>) {
+    counter.heals += 1;
+}
+
+#[test]
+fn removed_sagas_no_longer_handle_events() {
+    let mut app = App::new();
+    app.init_resource::<Counter>();
+    let combat = app.add_saga(Update, (attack, take_damage));
+    let peace = app.add_saga(Update, (await_event::<Attack, Truce>(), make_peace));
+    app.world_mut().send_event(Attack);
+    app.update();
+    assert_eq!(app.world().resource::<Counter>().attacks, 1);
+
+    // The waiting saga instance isn't resumed, and new events don't start saga instances.
+    app.world_mut().remove_saga(combat);
+    app.world_mut().remove_saga(peace);
+    app.world_mut().send_event(Truce);
+    app.world_mut().send_event(Attack);
+    app.update();
+    let counter = app.world().resource::<Counter>();
+    assert_eq!((counter.attacks, counter.heals), (1, 0));
+}
+
+fn parry(_: Attack) -> Result<Damage, Truce> {
+    Err(Truce)
+}
+
+fn tally(_: Gathered<Damage>) {}
+
+fn forgive(_: Truce) {}
+
+fn undo_damage(_: Damage) {}
+
+fn registered_systems(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<SystemIdMarker>>()
+        .iter(app.world())
+        .count()
+}
+
+#[test]
+fn removed_sagas_unregister_their_systems() {
+    let (mut app, _) = app();
+    let registered = registered_systems(&mut app);
+    let sagas = [
+        app.add_saga(Update, (gather((attack, attack)), tally)),
+        app.add_saga(Update, parry.retry(RetryPolicy::times(1)).ok(take_damage).err(forgive)),
+        app.add_saga(Update, (attack.compensate(undo_damage), take_damage)),
+    ];
+    assert!(registered_systems(&mut app) > registered);
+    for saga in sagas {
+        app.world_mut().remove_saga(saga);
+    }
+    assert_eq!(registered, registered_systems(&mut app));
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::{App, EventReader, PostUpdate, ResMut, Resource, Update};
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+
+#[derive(Default, Resource)]
+struct Heard {
+    by_saga: Vec<u8>,
+    by_reader: Vec<u8>,
+    hits: Vec<u8>,
+}
+
+#[saga_event]
+struct Damage(u8);
+
+#[saga_event]
+struct Hit(u8);
+
+fn take_damage(Damage(damage): Damage, mut heard: ResMut<Heard>) -> Hit {
+    heard.by_saga.push(damage);
+    Hit(damage)
+}
+
+fn show_hit(Hit(damage): Hit, mut heard: ResMut<Heard>) {
+    heard.hits.push(damage);
+}
+
+fn play_sound(mut damage: EventReader<Damage>, mut heard: ResMut<Heard>) {
+    for Damage(damage) in damage.read() {
+        heard.by_reader.push(*damage);
+    }
+}
+
+#[test]
+fn shared_events_are_seen_by_sagas_and_readers() {
+    let mut app = App::new();
+    app.init_resource::<Heard>();
+    app.share_saga_events::<Damage>()
+        .add_systems(PostUpdate, play_sound);
+    app.add_saga(Update, (take_damage, show_hit));
+
+    app.world_mut().send_event(Damage(4));
+    app.update();
+    app.world_mut().send_event(Damage(2));
+    app.update();
+    app.update();
+
+    let heard = app.world().resource::<Heard>();
+    assert_eq!(vec![4, 2], heard.by_saga);
+    assert_eq!(vec![4, 2], heard.hits);
+    assert_eq!(vec![4, 2], heard.by_reader);
+}
+
+#[test]
+fn unshared_events_are_drained() {
+    let mut app = App::new();
+    app.init_resource::<Heard>();
+    app.add_saga(Update, (take_damage, show_hit));
+    app.add_systems(PostUpdate, play_sound);
+
+    app.world_mut().send_event(Damage(4));
+    app.update();
+
+    let heard = app.world().resource::<Heard>();
+    assert_eq!(vec![4], heard.by_saga);
+    assert!(heard.by_reader.is_empty());
+}
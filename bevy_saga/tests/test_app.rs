@@ -0,0 +1,110 @@
+use bevy::prelude::{Event, Events, Update};
+use bevy_saga::SagaRegistry;
+use bevy_saga::prelude::{BevySagaUtil, ErrStage, OkStage, SagaTestApp};
+use bevy_saga::saga_event;
+
+#[saga_event]
+struct AttackTrigger(u8);
+
+#[saga_event]
+struct Offense(u8);
+
+#[saga_event]
+struct Damage {
+    damage: u8,
+}
+
+#[saga_event]
+struct Blocked;
+
+fn calculate_offense(AttackTrigger(weapon): AttackTrigger) -> Offense {
+    Offense(weapon + 1)
+}
+
+fn calculate_defense(Offense(offense): Offense) -> Result<Damage, Blocked> {
+    offense.checked_sub(2).filter(|damage| *damage > 0).map(|damage| Damage { damage }).ok_or(Blocked)
+}
+
+fn perform_attack(_: Damage) {}
+
+fn block(_: Blocked) {}
+
+fn app() -> SagaTestApp {
+    let mut app = SagaTestApp::new();
+    app.add_saga(
+        Update,
+        (calculate_offense, calculate_defense.ok(perform_attack).err(block)),
+    );
+    app
+}
+
+#[test]
+fn asserts_on_the_emitted_events_and_the_path() {
+    let mut app = app();
+    app.send(AttackTrigger(3)).update();
+
+    app.assert_emitted::<Offense>(|offense| offense.0 == 4);
+    app.assert_emitted::<Damage>(|damage| damage.damage == 2);
+    app.assert_not_emitted::<Blocked>();
+    app.assert_path((calculate_offense, calculate_defense, perform_attack));
+
+    app.clear();
+    app.send(AttackTrigger(0)).update();
+    app.assert_not_emitted::<Damage>();
+    app.assert_path(["calculate_offense", "calculate_defense", "block"]);
+}
+
+#[test]
+#[should_panic(expected = "Expected an event of type")]
+fn fails_when_no_emitted_event_matches() {
+    let mut app = app();
+    app.send(AttackTrigger(3)).update();
+
+    app.assert_emitted::<Damage>(|damage| damage.damage == 5);
+}
+
+#[test]
+#[should_panic(expected = "Expected no event of type")]
+fn captures_events_that_no_saga_handles() {
+    let mut app = SagaTestApp::new();
+    app.add_event::<Damage>();
+    app.add_event::<Blocked>();
+    app.capture::<Blocked>();
+    let defense = app.add_result_handler(calculate_defense);
+    app.add_systems(Update, defense);
+    app.send(Offense(1)).update();
+
+    app.assert_not_emitted::<Blocked>();
+}
+
+#[test]
+#[should_panic(expected = "are not captured")]
+fn fails_on_events_that_are_not_captured() {
+    let mut app = SagaTestApp::new();
+    app.add_event::<Damage>();
+    app.add_event::<Blocked>();
+    let defense = app.add_result_handler(calculate_defense);
+    app.add_systems(Update, defense);
+    app.send(Offense(1)).update();
+
+    app.assert_not_emitted::<Blocked>();
+}
+
+/// An event that can't be cloned, so it isn't a saga event.
+#[derive(Event)]
+struct Logged;
+
+fn log(_: Offense) -> Logged {
+    Logged
+}
+
+#[test]
+fn steps_emit_plain_events() {
+    let mut app = SagaTestApp::new();
+    app.add_event::<Logged>();
+    let log = app.add_event_processor(log);
+    app.add_systems(Update, log);
+    app.send(Offense(1)).update();
+
+    assert_eq!(app.world().resource::<Events<Logged>>().len(), 1);
+}
@@ -0,0 +1,112 @@
+use bevy::prelude::{App, ResMut, Resource, Update};
+use bevy::time::{Time, Virtual};
+use bevy_saga::prelude::{await_event, Awaited, Correlated, PendingAwaits, TimedOut, TimeoutStage};
+use bevy_saga::SagaRegistry;
+use bevy_saga::saga_event;
+use std::time::Duration;
+
+#[derive(Default, Resource)]
+struct Dialogs {
+    chosen: Vec<u8>,
+    closed: Vec<u8>,
+}
+
+#[saga_event]
+struct DialogOpened(u8);
+
+#[saga_event]
+struct Choice(u8);
+
+impl Correlated for DialogOpened {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Correlated for Choice {
+    type Key = u8;
+
+    fn correlation_key(&self) -> u8 {
+        self.0
+    }
+}
+
+fn continue_dialog(Awaited(DialogOpened(speaker), _): Awaited<DialogOpened, Choice>, mut dialogs: ResMut<Dialogs>) {
+    dialogs.chosen.push(speaker);
+}
+
+fn close_dialog(TimedOut(DialogOpened(speaker)): TimedOut<DialogOpened>, mut dialogs: ResMut<Dialogs>) {
+    dialogs.closed.push(speaker);
+}
+
+fn advance(app: &mut App, seconds: u64) {
+    app.world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .advance_by(Duration::from_secs(seconds));
+    app.update();
+}
+
+#[test]
+fn suspended_saga_times_out() {
+    let mut app = App::new();
+    app.init_resource::<Dialogs>();
+    app.add_saga(
+        Update,
+        (
+            await_event::<DialogOpened, Choice>().timeout(Duration::from_secs(5), close_dialog),
+            continue_dialog,
+        ),
+    );
+
+    app.world_mut().send_event(DialogOpened(1));
+    app.world_mut().send_event(DialogOpened(2));
+    app.update();
+
+    app.world_mut().send_event(Choice(2));
+    advance(&mut app, 3);
+    assert_eq!(vec![2], app.world().resource::<Dialogs>().chosen);
+    assert!(app.world().resource::<Dialogs>().closed.is_empty());
+
+    advance(&mut app, 3);
+    assert_eq!(vec![1], app.world().resource::<Dialogs>().closed);
+    assert!(app.world().resource::<PendingAwaits<DialogOpened, Choice>>().is_empty());
+
+    app.world_mut().send_event(Choice(1));
+    advance(&mut app, 3);
+    assert_eq!(vec![2], app.world().resource::<Dialogs>().chosen);
+    assert_eq!(vec![1], app.world().resource::<Dialogs>().closed);
+}
+
+#[saga_event]
+struct Conversation;
+
+fn open_dialogs(_: Conversation) -> Vec<DialogOpened> {
+    vec![DialogOpened(1), DialogOpened(2)]
+}
+
+#[test]
+fn every_event_of_a_saga_instance_has_its_own_deadline() {
+    let mut app = App::new();
+    app.init_resource::<Dialogs>();
+    app.add_saga(
+        Update,
+        (
+            open_dialogs,
+            await_event::<DialogOpened, Choice>().timeout(Duration::from_secs(5), close_dialog),
+            continue_dialog,
+        ),
+    );
+
+    app.world_mut().send_event(Conversation);
+    app.update();
+    app.world_mut().send_event(Choice(2));
+    advance(&mut app, 3);
+    assert_eq!(vec![2], app.world().resource::<Dialogs>().chosen);
+
+    // Only the dialog that is still open times out.
+    advance(&mut app, 3);
+    assert_eq!(vec![1], app.world().resource::<Dialogs>().closed);
+    assert!(app.world().resource::<PendingAwaits<DialogOpened, Choice>>().is_empty());
+}
@@ -0,0 +1,182 @@
+use crate::graph::SagaEdgeLabel;
+use crate::instance::{InstanceStore, SagaInstance, SagaInstanceId, SagaWriter};
+use crate::processor::EventProcessor;
+use crate::timeout::{SuspendedInstances, SuspendingStep};
+use crate::{SagaEvent, extension::BevySagaUtil};
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::{App, Event, In, IntoScheduleConfigs, ResMut, Resource, SystemParamFunction};
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{AsyncComputeTaskPool, Task, TaskPool};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use variadics_please::all_tuples;
+
+/// A resource that holds the saga instances that are waiting for an async processor to produce an
+/// event of type `Out`.
+#[derive(Resource)]
+pub struct PendingTasks<Out> {
+    tasks: Vec<(SagaInstanceId, Task<Out>)>,
+}
+
+impl<Out> Default for PendingTasks<Out> {
+    fn default() -> Self {
+        PendingTasks { tasks: vec![] }
+    }
+}
+
+impl<Out> PendingTasks<Out> {
+    /// Iterates over all saga instances that are waiting for a task.
+    pub fn iter(&self) -> impl Iterator<Item = SagaInstanceId> {
+        self.tasks.iter().map(|(instance, _)| *instance)
+    }
+
+    /// The number of running tasks.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns true if no task is running.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+impl<Out> InstanceStore for PendingTasks<Out>
+where
+    Out: Event,
+{
+    fn holds(&self, instance: SagaInstanceId) -> bool {
+        self.iter().any(|held| held == instance)
+    }
+
+    fn cancel(&mut self, instance: SagaInstanceId) {
+        self.abandon(instance);
+    }
+}
+
+impl<Out> SuspendedInstances for PendingTasks<Out>
+where
+    Out: Event,
+{
+    /// Drops the tasks of the saga instance, which cancels them.
+    fn abandon(&mut self, instance: SagaInstanceId) {
+        self.tasks.retain(|(held, _)| *held != instance);
+    }
+}
+
+pub(crate) fn spawn_task<Fut>(
+    In(future): In<Fut>,
+    instance: SagaInstance,
+    mut tasks: ResMut<PendingTasks<Fut::Output>>,
+) where
+    Fut: Future + Send + 'static,
+    Fut::Output: Event,
+{
+    let task = AsyncComputeTaskPool::get_or_init(TaskPool::default).spawn(SyncFuture(future));
+    tasks.tasks.push((instance.current(), task));
+}
+
+/// Wraps a future that is only Send, because some task pools require the futures they spawn to be
+/// Sync as well.
+struct SyncFuture<Fut>(Fut);
+
+// SAFETY: The future is only accessed through a mutable reference, so it's never shared between
+// threads.
+unsafe impl<Fut> Sync for SyncFuture<Fut> where Fut: Send {}
+
+impl<Fut> Future for SyncFuture<Fut>
+where
+    Fut: Future,
+{
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: The future is pinned with the wrapper, because it's never moved out of it.
+        unsafe { self.map_unchecked_mut(|wrapper| &mut wrapper.0) }.poll(cx)
+    }
+}
+
+pub(crate) fn poll_tasks<Out>(mut tasks: ResMut<PendingTasks<Out>>, mut writer: SagaWriter<Out>)
+where
+    Out: Event,
+{
+    tasks.tasks.retain_mut(|(instance, task)| match check_ready(task) {
+        Some(response) => {
+            writer.write_as(*instance, response);
+            false
+        }
+        None => true,
+    });
+}
+
+pub struct AsyncProcessor<T>(T);
+
+impl<SPF, M, In, Fut> EventProcessor<AsyncProcessor<(M,)>> for SPF
+where
+    In: SagaEvent,
+    Fut: Future + Send + 'static,
+    Fut::Output: Event,
+    SPF: SystemParamFunction<M, In = In, Out = Fut>,
+    M: 'static,
+{
+    type In = In;
+    type Out = Fut::Output;
+
+    fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        app.add_async_processor::<In, Fut, _>(self)
+    }
+}
+
+impl<SPF, M, In, Fut> SuspendingStep<AsyncProcessor<(M,)>> for SPF
+where
+    In: SagaEvent,
+    Fut: Future + Send + 'static,
+    Fut::Output: Event,
+    SPF: SystemParamFunction<M, In = In, Out = Fut>,
+    M: 'static,
+{
+    type Suspended = PendingTasks<Fut::Output>;
+}
+
+macro_rules! impl_async_processor {
+    ($(#[$meta:meta])* $(($SPF:ident, $p:ident, $M:ident)),*) => {
+        impl<PROC, MPROC, $($SPF,)* $($M,)* In, Fut> EventProcessor<AsyncProcessor<(MPROC, $($M,)*)>> for (PROC, $($SPF,)*)
+        where
+            In: SagaEvent,
+            Fut: Future + Send + 'static,
+            Fut::Output: Event,
+            PROC: SystemParamFunction<MPROC, In = In, Out = Fut>,
+            $($SPF: SystemParamFunction<$M, In = In, Out = ()>,)*
+            MPROC: 'static,
+            $($M: 'static,)*
+        {
+            type In = In;
+            type Out = Fut::Output;
+
+            fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+                let (proc, $($p,)*) = self;
+                (
+                    app.add_async_processor::<In, Fut, _>(proc),
+                    $(app.add_step_handler::<In, _>($p, Some(SagaEdgeLabel::Sibling)),)*
+                )
+                    .into_configs()
+            }
+        }
+
+        impl<PROC, MPROC, $($SPF,)* $($M,)* In, Fut> SuspendingStep<AsyncProcessor<(MPROC, $($M,)*)>> for (PROC, $($SPF,)*)
+        where
+            In: SagaEvent,
+            Fut: Future + Send + 'static,
+            Fut::Output: Event,
+            PROC: SystemParamFunction<MPROC, In = In, Out = Fut>,
+            $($SPF: SystemParamFunction<$M, In = In, Out = ()>,)*
+            MPROC: 'static,
+            $($M: 'static,)*
+        {
+            type Suspended = PendingTasks<Fut::Output>;
+        }
+    }
+}
+
+all_tuples!(impl_async_processor, 1, 15, SPF, p, M);
@@ -0,0 +1,278 @@
+use crate::graph::SagaGraph;
+use crate::instance::{InstanceStore, SagaInstance, SagaInstanceId, SagaInstances, SagaWriter};
+use crate::processor::EventProcessor;
+use crate::timeout::{SuspendedInstances, SuspendingStep};
+use crate::extension::{SagaRegistrations, add_saga_consumer};
+use crate::{SagaEvent, extension::BevySagaUtil};
+use bevy::app::App;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Event, IntoScheduleConfigs, ResMut, Resource, SystemInput};
+use std::any::type_name;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Events that can be matched with each other by a correlation key.
+///
+/// Two events correlate when their correlation keys are equal. Implement this trait for the events
+/// you pass to [await_event].
+///
+/// ```
+/// # use bevy::prelude::Entity;
+/// use bevy_saga_impl::prelude::Correlated;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct DialogOpened {
+///     speaker: Entity,
+/// }
+///
+/// impl Correlated for DialogOpened {
+///     type Key = Entity;
+///
+///     fn correlation_key(&self) -> Entity {
+///         self.speaker
+///     }
+/// }
+/// ```
+pub trait Correlated {
+    type Key: Eq + Hash + Clone + Send + Sync + 'static;
+
+    fn correlation_key(&self) -> Self::Key;
+}
+
+/// The event that is propagated through a saga once an [awaited](await_event) event has arrived.
+///
+/// The first field holds the event the saga was waiting with, the second field holds the event
+/// the saga was waiting for.
+#[derive(Clone)]
+pub struct Awaited<A, E>(pub A, pub E);
+
+impl<A, E> Event for Awaited<A, E>
+where
+    A: SagaEvent,
+    E: SagaEvent,
+{
+    type Traversal = ();
+}
+
+impl<A, E> SystemInput for Awaited<A, E>
+where
+    A: SagaEvent,
+    E: SagaEvent,
+{
+    type Param<'i> = Awaited<A, E>;
+    type Inner<'i> = Awaited<A, E>;
+
+    fn wrap(this: Self::Inner<'_>) -> Self::Param<'_> {
+        this
+    }
+}
+
+impl<A, E> SagaEvent for Awaited<A, E>
+where
+    A: SagaEvent,
+    E: SagaEvent,
+{
+}
+
+/// A resource that holds all saga instances that are waiting for an event of type `E`.
+///
+/// The instances are grouped by the correlation key of the event they are waiting with.
+#[derive(Resource)]
+pub struct PendingAwaits<A, E>
+where
+    A: Correlated,
+{
+    pending: HashMap<A::Key, Vec<(SagaInstanceId, A)>>,
+    _marker: PhantomData<fn() -> E>,
+}
+
+impl<A, E> Default for PendingAwaits<A, E>
+where
+    A: Correlated,
+{
+    fn default() -> Self {
+        PendingAwaits {
+            pending: HashMap::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A, E> PendingAwaits<A, E>
+where
+    A: Correlated,
+{
+    /// Iterates over all waiting saga instances and the events they are waiting with.
+    pub fn iter(&self) -> impl Iterator<Item = (SagaInstanceId, &A)> {
+        self.pending
+            .values()
+            .flatten()
+            .map(|(instance, event)| (*instance, event))
+    }
+
+    /// Returns true if any saga instance is waiting for an event with this correlation key.
+    pub fn contains_key(&self, key: &A::Key) -> bool {
+        self.pending.contains_key(key)
+    }
+
+    /// The number of waiting saga instances.
+    pub fn len(&self) -> usize {
+        self.pending.values().map(Vec::len).sum()
+    }
+
+    /// Returns true if no saga instance is waiting.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    fn park(&mut self, instance: SagaInstanceId, event: A) {
+        self.pending
+            .entry(event.correlation_key())
+            .or_default()
+            .push((instance, event));
+    }
+
+    fn resume(&mut self, key: &A::Key) -> Vec<(SagaInstanceId, A)> {
+        self.pending.remove(key).unwrap_or_default()
+    }
+}
+
+impl<A, E> InstanceStore for PendingAwaits<A, E>
+where
+    A: Correlated + Send + Sync + 'static,
+    E: 'static,
+{
+    fn holds(&self, instance: SagaInstanceId) -> bool {
+        self.iter().any(|(held, _)| held == instance)
+    }
+
+    fn cancel(&mut self, instance: SagaInstanceId) {
+        self.abandon(instance);
+    }
+}
+
+impl<A, E> SuspendedInstances for PendingAwaits<A, E>
+where
+    A: Correlated + Send + Sync + 'static,
+    E: 'static,
+{
+    fn abandon(&mut self, instance: SagaInstanceId) {
+        self.pending.retain(|_, waiting| {
+            waiting.retain(|(held, _)| *held != instance);
+            !waiting.is_empty()
+        });
+    }
+}
+
+pub struct AwaitEvent<A, E>(PhantomData<fn() -> (A, E)>);
+
+pub struct AwaitEventM;
+
+impl<A, E> EventProcessor<AwaitEventM> for AwaitEvent<A, E>
+where
+    A: SagaEvent + Correlated,
+    E: SagaEvent + Correlated<Key = A::Key>,
+{
+    type In = A;
+    type Out = Awaited<A, E>;
+
+    fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        app.init_resource::<PendingAwaits<A, E>>();
+        let name = format!("await_event<{}, {}>", type_name::<A>(), type_name::<E>());
+        let step = app.record_saga_step::<A>(&name, None);
+        app.world_mut()
+            .resource_mut::<SagaGraph>()
+            .add_input(type_name::<E>(), step, None);
+        app.record_saga_flow::<E, Awaited<A, E>>(step, None);
+        let park = app.add_event_handler(
+            |event: A, instance: SagaInstance, mut pending: ResMut<PendingAwaits<A, E>>| {
+                pending.park(instance.current(), event);
+            },
+        );
+        // The events of type `E` only resume the waiting saga instances, so they don't start
+        // instances of their own.
+        let resume = add_saga_consumer(
+            app,
+            |event: E, mut pending: ResMut<PendingAwaits<A, E>>, mut writer: SagaWriter<Awaited<A, E>>| {
+                for (instance, waiting) in pending.resume(&event.correlation_key()) {
+                    writer.write_as(instance, Awaited(waiting, event.clone()));
+                }
+            },
+        );
+        // Saga instances are parked before the events of the same update resume them.
+        app.world_mut()
+            .resource_mut::<SagaRegistrations>()
+            .order::<A, E>();
+        app.world_mut()
+            .resource_mut::<SagaInstances>()
+            .register_store::<PendingAwaits<A, E>>();
+        (park, resume).into_configs()
+    }
+}
+
+impl<A, E> SuspendingStep<AwaitEventM> for AwaitEvent<A, E>
+where
+    A: SagaEvent + Correlated,
+    E: SagaEvent + Correlated<Key = A::Key>,
+{
+    type Suspended = PendingAwaits<A, E>;
+}
+
+/// A saga step that suspends the saga instance until an event of type `E` with a matching
+/// [correlation key](Correlated) is sent.
+///
+/// The step receives an event of type `A` and parks the saga instance in the
+/// [PendingAwaits](PendingAwaits) resource. Once an `E` with the same correlation key is sent, in
+/// the same update or in a later one, the saga instance resumes with an [Awaited] event that holds
+/// both events. Every `E` resumes all saga instances that are waiting for its key. Events of type
+/// `E` don't start saga instances of their own, so those that nobody is waiting for are dropped.
+///
+/// Add a [timeout](crate::prelude::TimeoutStage) to the step to stop waiting after a while.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::Entity;
+/// use bevy_saga_impl::prelude::{await_event, Awaited, Correlated};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Talk(Entity);
+///
+/// #[saga_event]
+/// struct DialogOpened(Entity);
+///
+/// #[saga_event]
+/// struct Choice(Entity, u8);
+///
+/// impl Correlated for DialogOpened {
+///     type Key = Entity;
+///
+///     fn correlation_key(&self) -> Entity { self.0 }
+/// }
+///
+/// impl Correlated for Choice {
+///     type Key = Entity;
+///
+///     fn correlation_key(&self) -> Entity { self.0 }
+/// }
+///
+/// fn open_dialog(Talk(speaker): Talk, /* other queries or resources */) -> DialogOpened {
+///     DialogOpened(speaker)
+/// }
+///
+/// fn continue_dialog(Awaited(opened, choice): Awaited<DialogOpened, Choice>) {
+///     println!("Picked option {}.", choice.1)
+/// }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, (open_dialog, await_event::<DialogOpened, Choice>(), continue_dialog));
+/// ```
+pub fn await_event<A, E>() -> AwaitEvent<A, E>
+where
+    A: SagaEvent + Correlated,
+    E: SagaEvent + Correlated<Key = A::Key>,
+{
+    AwaitEvent(PhantomData)
+}
@@ -0,0 +1,248 @@
+use crate::SagaEvent;
+use crate::extension::{
+    BevySagaUtil, SagaRegistrations, add_inline_system_as, add_step_system, run_inline,
+};
+use crate::graph::SagaGraph;
+use crate::instance::{
+    CurrentSagaInstance, SagaInstanceId, SagaInstances, StepSystem, abandon_entry, release_instance,
+};
+use crate::processor::EventProcessor;
+use crate::util::StepFeed;
+use bevy::app::App;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{IntoScheduleConfigs, World};
+use std::any::TypeId;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+/// The branches of a [race](crate::prelude::race) or [gather](crate::prelude::gather) that are
+/// being added to a saga.
+///
+/// Every event that enters the branches starts an entry of the saga instance for every branch,
+/// which the branch runs as. This keeps the events of a saga instance apart, and tells which
+/// branch an output came from. The outputs of the branches pass a gate, which continues as the
+/// saga instance again.
+pub(crate) struct Branches<In, Out, S> {
+    feeds: HashMap<TypeId, StepFeed>,
+    /// Feed the branches with the events, one for every branch.
+    inlines: Vec<u64>,
+    /// The feeds that the branches pass their outputs on with.
+    outputs: Vec<StepFeed>,
+    entered: Arc<Mutex<Entered<S>>>,
+    _marker: PhantomData<fn(In) -> Out>,
+}
+
+/// The entries of the same event in all branches, with the state the gate keeps for them.
+pub(crate) struct Group<S> {
+    pub(crate) entries: Vec<SagaInstanceId>,
+    pub(crate) state: S,
+}
+
+/// The events that entered the branches and haven't passed the gate yet.
+pub(crate) struct Entered<S> {
+    /// The branch of every entry, and the first entry of its group.
+    entries: HashMap<SagaInstanceId, (usize, SagaInstanceId)>,
+    groups: HashMap<SagaInstanceId, Group<S>>,
+}
+
+impl<S> Default for Entered<S> {
+    fn default() -> Self {
+        Entered {
+            entries: HashMap::default(),
+            groups: HashMap::default(),
+        }
+    }
+}
+
+impl<S> Entered<S>
+where
+    S: Default,
+{
+    fn enter(&mut self, entries: Vec<SagaInstanceId>) {
+        let first = entries[0];
+        for (branch, entry) in entries.iter().enumerate() {
+            self.entries.insert(*entry, (branch, first));
+        }
+        let state = S::default();
+        self.groups.insert(first, Group { entries, state });
+    }
+
+    /// Looks up the entry that the current step runs as. Returns the saga instance it belongs to,
+    /// its branch and its group. Groups whose entries all finished are forgotten.
+    pub(crate) fn current(
+        &mut self,
+        world: &World,
+    ) -> Option<(SagaInstanceId, usize, SagaInstanceId)> {
+        let entry = world.resource::<CurrentSagaInstance>().get()?;
+        let instances = world.resource::<SagaInstances>();
+        let finished: Vec<_> = self
+            .groups
+            .iter()
+            .filter(|(_, group)| {
+                !group
+                    .entries
+                    .iter()
+                    .any(|entry| instances.is_running(*entry))
+            })
+            .map(|(first, _)| *first)
+            .collect();
+        for first in finished {
+            self.leave(first);
+        }
+        let (branch, first) = *self.entries.get(&entry)?;
+        Some((instances.entered(entry)?, branch, first))
+    }
+
+    pub(crate) fn group_mut(&mut self, first: SagaInstanceId) -> Option<&mut Group<S>> {
+        self.groups.get_mut(&first)
+    }
+
+    /// Forgets the group, so the outputs of its entries no longer pass the gate.
+    pub(crate) fn leave(&mut self, first: SagaInstanceId) -> Option<Group<S>> {
+        let group = self.groups.remove(&first)?;
+        for entry in &group.entries {
+            self.entries.remove(entry);
+        }
+        Some(group)
+    }
+}
+
+impl<In, Out, S> Branches<In, Out, S>
+where
+    In: SagaEvent,
+    Out: SagaEvent,
+    S: Default + Send + 'static,
+{
+    pub(crate) fn new(app: &mut App) -> Self {
+        app.init_resource::<SagaRegistrations>();
+        app.init_resource::<SagaInstances>();
+        Branches {
+            feeds: app.world().resource::<SagaRegistrations>().feeds(),
+            inlines: vec![],
+            outputs: vec![],
+            entered: Arc::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The events that entered the branches, shared with the gate.
+    pub(crate) fn entered(&self) -> Arc<Mutex<Entered<S>>> {
+        self.entered.clone()
+    }
+
+    /// Adds a branch. Until the next branch is added or the branches are finished, the steps that
+    /// are added for `In` run in this branch as well.
+    pub(crate) fn add_branch<M, Branch>(
+        &mut self,
+        app: &mut App,
+        branch: Branch,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        Branch: EventProcessor<M, In = In, Out = Out>,
+    {
+        let mut registrations = app.world_mut().resource_mut::<SagaRegistrations>();
+        let inline = registrations.next_inline();
+        registrations.restore_feeds(self.feeds.clone());
+        registrations.set_feed::<In>(StepFeed {
+            events: false,
+            inline: Some(inline),
+        });
+        self.inlines.push(inline);
+        let branch = branch.register_processor(app);
+        let output = app
+            .world()
+            .resource::<SagaRegistrations>()
+            .feeds()
+            .get(&TypeId::of::<Out>())
+            .copied()
+            .unwrap_or_default();
+        if !self.outputs.contains(&output) {
+            self.outputs.push(output);
+        }
+        branch
+    }
+
+    /// Adds the step that enters every event into all branches, and the gate that the outputs of
+    /// the branches pass. The gate is recorded as a step of the saga if it has a name. The steps
+    /// that follow are fed with the outputs of the gate.
+    pub(crate) fn finish<Rs>(
+        self,
+        app: &mut App,
+        name: Option<&str>,
+        gate: impl Fn(&mut World, Out) -> Option<(SagaInstanceId, Rs)> + Send + Sync + 'static,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        Rs: SagaEvent,
+    {
+        let Branches {
+            feeds,
+            inlines,
+            outputs,
+            entered,
+            ..
+        } = self;
+        app.world_mut()
+            .resource_mut::<SagaRegistrations>()
+            .restore_feeds(feeds.clone());
+        let system = StepSystem::Inline(Arc::new(move |world: &mut World, event: In| {
+            let Some(instance) = world.resource::<CurrentSagaInstance>().get() else {
+                return Ok(());
+            };
+            let mut instances = world.resource_mut::<SagaInstances>();
+            let entries: Vec<_> = inlines.iter().map(|_| instances.enter(instance)).collect();
+            entered.lock().unwrap().enter(entries.clone());
+            let result = inlines
+                .iter()
+                .zip(&entries)
+                .try_for_each(|(inline, entry)| run_inline(world, *entry, *inline, event.clone()));
+            for entry in entries {
+                release_instance(entry)(world);
+            }
+            result
+        }));
+        let enter = add_step_system(app, system);
+        if let Some(name) = name {
+            let step = app.record_saga_step::<Out>(name, None);
+            app.world_mut()
+                .resource_mut::<SagaGraph>()
+                .add_output(step, std::any::type_name::<Rs>(), None);
+        }
+        let inline = app
+            .world_mut()
+            .resource_mut::<SagaRegistrations>()
+            .next_inline();
+        let gate = Arc::new(gate);
+        let gates: Vec<_> = outputs
+            .into_iter()
+            .map(|output| {
+                app.world_mut()
+                    .resource_mut::<SagaRegistrations>()
+                    .set_feed::<Out>(output);
+                let gate = gate.clone();
+                add_inline_system_as(app, inline, move |world, event| gate(world, event))
+            })
+            .collect();
+        let mut registrations = app.world_mut().resource_mut::<SagaRegistrations>();
+        registrations.restore_feeds(feeds);
+        registrations.set_feed::<Rs>(StepFeed {
+            events: false,
+            inline: Some(inline),
+        });
+        let mut configs = enter;
+        for gate in gates {
+            configs = (configs, gate).into_configs();
+        }
+        configs
+    }
+}
+
+/// Abandons the entries of the other branches, so they stop and don't produce an output.
+pub(crate) fn abandon_others(world: &mut World, group: Group<impl Sized>, branch: usize) {
+    for (other, entry) in group.entries.into_iter().enumerate() {
+        if other != branch {
+            abandon_entry(world, entry);
+        }
+    }
+}
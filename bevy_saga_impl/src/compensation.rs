@@ -0,0 +1,158 @@
+use crate::instance::{SagaId, SagaInstanceId, run_as_instance};
+use crate::processor::EventProcessor;
+use crate::{SagaEvent, extension::BevySagaUtil};
+use bevy::app::App;
+use bevy::ecs::error::BevyError;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::{ScheduleSystem, SystemId};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{IntoScheduleConfigs, Resource, SystemParamFunction, World};
+
+type Compensation = Box<dyn FnOnce(&mut World) -> Result<(), BevyError> + Send + Sync>;
+
+/// A resource used by bevy_saga to remember the compensations of the steps that already succeeded
+/// in a saga instance.
+///
+/// It's not recommended to use this resource in your own code.
+#[derive(Resource, Default)]
+pub struct Compensations {
+    stacks: HashMap<SagaInstanceId, Vec<Compensation>>,
+}
+
+impl Compensations {
+    /// Remembers that `undo` has to be executed with `event` if the saga instance fails.
+    pub(crate) fn record<R>(&mut self, instance: SagaInstanceId, undo: SystemId<R, ()>, event: R)
+    where
+        R: SagaEvent,
+    {
+        self.stacks.entry(instance).or_default().push(Box::new(move |world| {
+            run_as_instance(world, instance, |world| world.run_system_with(undo, event))?;
+            Ok(())
+        }));
+    }
+
+    pub(crate) fn forget(&mut self, instance: SagaInstanceId) {
+        self.stacks.remove(&instance);
+    }
+
+    /// Forgets the compensations of all instances of a removed saga.
+    pub(crate) fn forget_saga(&mut self, saga: SagaId) {
+        self.stacks.retain(|instance, _| instance.saga() != saga);
+    }
+}
+
+/// Runs the compensations of all steps that already succeeded in a saga instance, the most recent
+/// step first.
+pub(crate) fn compensate(instance: SagaInstanceId) -> impl FnOnce(&mut World) -> Result<(), BevyError> {
+    move |world| {
+        let stack = world
+            .get_resource_mut::<Compensations>()
+            .and_then(|mut compensations| compensations.stacks.remove(&instance))
+            .unwrap_or_default();
+        for compensation in stack.into_iter().rev() {
+            compensation(world)?;
+        }
+        Ok(())
+    }
+}
+
+struct Compensated<Processor, Undo> {
+    processor: Processor,
+    undo: Undo,
+}
+
+pub struct CompensatedM<T>(T);
+
+impl<Processor, Undo, MP, MU> EventProcessor<CompensatedM<(MP, MU)>>
+    for Compensated<Processor, Undo>
+where
+    Processor: EventProcessor<MP>,
+    Processor::Out: SagaEvent,
+    Undo: SystemParamFunction<MU, In = Processor::Out, Out = ()>,
+    MU: 'static,
+{
+    type In = Processor::In;
+    type Out = Processor::Out;
+
+    fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        let Compensated { processor, undo } = self;
+        (
+            processor.register_processor(app),
+            app.add_compensation::<Processor::Out, _>(undo),
+        )
+            .chain()
+    }
+}
+
+/// This trait provides the `compensate` method on [event processors](EventProcessor).
+///
+/// A compensation undoes the work of a step that already succeeded. It receives the output event
+/// of the step it compensates.
+///
+/// When a [result processor](crate::prelude::OkStage) further down the same saga returns Err, the
+/// compensations of all steps that already succeeded in that saga instance are executed in
+/// reverse order, before the Err value is propagated through the Err saga. If the saga finishes
+/// without an Err, the compensations are forgotten.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// use bevy_saga_impl::prelude::{CompensateStage, ErrStage, OkStage};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Craft;
+///
+/// #[saga_event]
+/// struct Reserved;
+///
+/// #[saga_event]
+/// struct Crafted;
+///
+/// #[saga_event]
+/// struct Failed;
+///
+/// fn reserve_materials(_: Craft, /* other queries or resources */) -> Reserved { Reserved }
+/// fn release_materials(_: Reserved, /* other queries or resources */) { }
+/// fn craft(_: Reserved, /* other queries or resources */) -> Result<Crafted, Failed> { Err(Failed) }
+/// fn done(_: Crafted) { }
+/// fn failed(_: Failed) { }
+///
+/// # let mut app = App::new();
+/// // When `craft` fails, `release_materials` is executed before `failed`.
+/// app.add_saga(Update, (
+///     reserve_materials.compensate(release_materials),
+///     craft.ok(done).err(failed),
+/// ));
+/// ```
+pub trait CompensateStage<MP>: EventProcessor<MP> + Sized
+where
+    Self::Out: SagaEvent,
+{
+    fn compensate<Undo, MU>(
+        self,
+        undo: Undo,
+    ) -> impl EventProcessor<CompensatedM<(MP, MU)>, In = Self::In, Out = Self::Out>
+    where
+        Undo: SystemParamFunction<MU, In = Self::Out, Out = ()>,
+        MU: 'static;
+}
+
+impl<Processor, MP> CompensateStage<MP> for Processor
+where
+    Processor: EventProcessor<MP>,
+    Processor::Out: SagaEvent,
+{
+    fn compensate<Undo, MU>(
+        self,
+        undo: Undo,
+    ) -> impl EventProcessor<CompensatedM<(MP, MU)>, In = Self::In, Out = Self::Out>
+    where
+        Undo: SystemParamFunction<MU, In = Self::Out, Out = ()>,
+        MU: 'static,
+    {
+        Compensated {
+            processor: self,
+            undo,
+        }
+    }
+}
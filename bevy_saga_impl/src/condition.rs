@@ -0,0 +1,407 @@
+use crate::SagaEvent;
+use crate::diagnostics::measure_step;
+use crate::extension::SagaRegistrations;
+use crate::handler::EventHandler;
+use crate::lifecycle::{SagaStepCompleted, emit};
+use crate::handle::SagaStates;
+use crate::instance::{InstanceStore, SagaInstanceId, SagaInstances, StepSystem, run_saga_step};
+use crate::processor::EventProcessor;
+use crate::result_processor::ResultProcessor;
+use crate::retry::RetryPolicy;
+use crate::timeout::SuspendingStep;
+use bevy::app::App;
+use bevy::ecs::error::{BevyError, ErrorContext};
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::{BoxedSystem, ScheduleSystem, SystemId};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Condition, IntoSystem, Resource, World};
+use std::any::{TypeId, type_name};
+use std::sync::Arc;
+
+/// Describes what happens to an event when the run condition of a step is not met.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConditionPolicy {
+    /// The event is dropped. The saga instance finishes without running the remaining steps.
+    #[default]
+    Consume,
+    /// The event is kept until the condition is met. The condition is checked again in every
+    /// update, and the saga instance continues as soon as it is met.
+    Keep,
+}
+
+/// A run condition of a step, registered as a system.
+#[derive(Clone, Copy)]
+pub(crate) struct StepCondition {
+    system: SystemId<(), bool>,
+    policy: ConditionPolicy,
+}
+
+impl StepCondition {
+    /// Runs the condition, unless its result was already cached while processing the current
+    /// events.
+    fn is_met(&self, world: &mut World) -> Result<bool, BevyError> {
+        let cached = world
+            .get_resource::<ConditionResults>()
+            .and_then(|results| results.0.as_ref()?.get(&self.system).copied());
+        if let Some(met) = cached {
+            return Ok(met);
+        }
+        let met = world.run_system(self.system)?;
+        if let Some(results) = world
+            .get_resource_mut::<ConditionResults>()
+            .and_then(|results| results.into_inner().0.as_mut())
+        {
+            results.insert(self.system, met);
+        }
+        Ok(met)
+    }
+}
+
+/// The results of the run conditions that were evaluated while processing the current events.
+/// Every condition is only evaluated once for all steps and saga instances that process them.
+#[derive(Default, Resource)]
+pub(crate) struct ConditionResults(Option<HashMap<SystemId<(), bool>, bool>>);
+
+/// Starts caching the results of run conditions.
+pub(crate) fn cache_conditions(world: &mut World) {
+    world.get_resource_or_init::<ConditionResults>().0 = Some(HashMap::default());
+}
+
+/// Stops caching the results of run conditions, so they are evaluated again for the next events.
+pub(crate) fn forget_conditions(world: &mut World) {
+    if let Some(mut results) = world.get_resource_mut::<ConditionResults>() {
+        results.0 = None;
+    }
+}
+
+/// A processor or handler of a saga together with the run conditions of the step it belongs to.
+pub(crate) struct ConditionalStep<R>
+where
+    R: SagaEvent,
+{
+    pub(crate) system: StepSystem<R>,
+    pub(crate) conditions: Vec<StepCondition>,
+    /// The name of the step in the [SagaGraph](crate::prelude::SagaGraph). Systems that only help
+    /// another step, like the recording of a compensation, have no name.
+    pub(crate) name: Option<Arc<str>>,
+    /// The event types the step produces.
+    pub(crate) outputs: Vec<TypeId>,
+}
+
+impl<R> Clone for ConditionalStep<R>
+where
+    R: SagaEvent,
+{
+    fn clone(&self) -> Self {
+        ConditionalStep {
+            system: self.system.clone(),
+            conditions: self.conditions.clone(),
+            name: self.name.clone(),
+            outputs: self.outputs.clone(),
+        }
+    }
+}
+
+impl<R> ConditionalStep<R>
+where
+    R: SagaEvent,
+{
+    /// Returns true if the step keeps its events when one of its conditions is not met.
+    pub(crate) fn keeps_events(&self) -> bool {
+        self.conditions
+            .iter()
+            .any(|condition| condition.policy == ConditionPolicy::Keep)
+    }
+
+    /// The systems of the conditions of the step.
+    pub(crate) fn condition_systems(&self) -> impl Iterator<Item = SystemId<(), bool>> {
+        self.conditions.iter().map(|condition| condition.system)
+    }
+
+    /// Runs the step on behalf of a saga instance if all of its conditions are met. Errors are
+    /// passed to the error handler of the saga, if it has one.
+    pub(crate) fn run(
+        self,
+        instance: SagaInstanceId,
+        event: R,
+    ) -> impl FnOnce(&mut World) -> Result<(), BevyError> {
+        move |world| {
+            let step = self.name.clone();
+            let Err(error) = self.run_unhandled(instance, event)(world) else {
+                return Ok(());
+            };
+            let states = world.resource::<SagaStates>();
+            let Some(handler) = states.error_handler(instance.saga()) else {
+                return Err(error);
+            };
+            let step = step.as_deref().unwrap_or(type_name::<R>());
+            let saga = states.name(instance.saga());
+            handler(error, ErrorContext::Command {
+                name: format!("{step} of {saga}").into(),
+            });
+            Ok(())
+        }
+    }
+
+    fn run_unhandled(
+        self,
+        instance: SagaInstanceId,
+        event: R,
+    ) -> impl FnOnce(&mut World) -> Result<(), BevyError> {
+        move |world| {
+            if world.resource::<SagaInstances>().is_cancelled(instance) {
+                return Ok(());
+            }
+            let mut unmet = vec![];
+            for condition in &self.conditions {
+                if !condition.is_met(world)? {
+                    unmet.push(condition.policy);
+                }
+            }
+            if unmet.is_empty() {
+                let run = run_saga_step(self.system, instance, event);
+                measure_step(world, instance, self.name.as_ref(), run)?;
+                if let Some(step) = self.name {
+                    let name = world.resource::<SagaStates>().name(instance.saga());
+                    emit(SagaStepCompleted {
+                        name,
+                        instance: world.resource::<SagaInstances>().root(instance),
+                        step,
+                        event: type_name::<R>(),
+                    })(world);
+                }
+            } else if unmet.contains(&ConditionPolicy::Keep) {
+                world
+                    .resource_mut::<HeldEvents<R>>()
+                    .held
+                    .push((self, instance, event));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// A resource that holds the events of type `R` that wait for the run conditions of a step to be
+/// met.
+#[derive(Resource)]
+pub struct HeldEvents<R>
+where
+    R: SagaEvent,
+{
+    held: Vec<(ConditionalStep<R>, SagaInstanceId, R)>,
+}
+
+impl<R> Default for HeldEvents<R>
+where
+    R: SagaEvent,
+{
+    fn default() -> Self {
+        HeldEvents { held: vec![] }
+    }
+}
+
+impl<R> HeldEvents<R>
+where
+    R: SagaEvent,
+{
+    /// Iterates over all saga instances that wait for a run condition and their events.
+    pub fn iter(&self) -> impl Iterator<Item = (SagaInstanceId, &R)> {
+        self.held.iter().map(|(_, instance, event)| (*instance, event))
+    }
+
+    /// The number of held events.
+    pub fn len(&self) -> usize {
+        self.held.len()
+    }
+
+    /// Returns true if no event is held.
+    pub fn is_empty(&self) -> bool {
+        self.held.is_empty()
+    }
+}
+
+impl<R> InstanceStore for HeldEvents<R>
+where
+    R: SagaEvent,
+{
+    fn holds(&self, instance: SagaInstanceId) -> bool {
+        self.iter().any(|(held, _)| held == instance)
+    }
+
+    fn cancel(&mut self, instance: SagaInstanceId) {
+        self.held.retain(|(_, held, _)| *held != instance);
+    }
+}
+
+/// Checks the conditions of all held events again.
+pub(crate) fn run_held_events<R>(world: &mut World) -> Result<(), BevyError>
+where
+    R: SagaEvent,
+{
+    let held = std::mem::take(&mut world.resource_mut::<HeldEvents<R>>().held);
+    cache_conditions(world);
+    let result = held
+        .into_iter()
+        .try_for_each(|(step, instance, event)| step.run(instance, event)(world));
+    forget_conditions(world);
+    result
+}
+
+/// A step or saga with a run condition. Create one with [saga_run_if](RunIfStage::saga_run_if).
+pub struct Conditioned<S> {
+    step: S,
+    condition: BoxedSystem<(), bool>,
+    policy: ConditionPolicy,
+}
+
+pub struct ConditionedM<T>(T);
+
+impl<S> Conditioned<S> {
+    /// Registers the step while its condition applies to all processors and handlers that are
+    /// added.
+    pub(crate) fn register_with<T>(self, app: &mut App, register: impl FnOnce(S, &mut App) -> T) -> T {
+        let Conditioned {
+            step,
+            condition,
+            policy,
+        } = self;
+        let condition = StepCondition {
+            system: app.world_mut().register_boxed_system(condition),
+            policy,
+        };
+        app.init_resource::<SagaRegistrations>();
+        app.world_mut()
+            .resource_mut::<SagaRegistrations>()
+            .push_condition(condition);
+        let registered = register(step, app);
+        app.world_mut()
+            .resource_mut::<SagaRegistrations>()
+            .pop_condition();
+        registered
+    }
+}
+
+impl<S, M> EventProcessor<ConditionedM<M>> for Conditioned<S>
+where
+    S: EventProcessor<M>,
+{
+    type In = S::In;
+    type Out = S::Out;
+
+    fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        self.register_with(app, S::register_processor)
+    }
+}
+
+impl<S, M> SuspendingStep<ConditionedM<M>> for Conditioned<S>
+where
+    S: SuspendingStep<M>,
+{
+    type Suspended = S::Suspended;
+}
+
+impl<S, M> ResultProcessor<ConditionedM<M>> for Conditioned<S>
+where
+    S: ResultProcessor<M>,
+{
+    type In = S::In;
+    type Ok = S::Ok;
+    type Err = S::Err;
+
+    fn register_result_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        self.register_with(app, S::register_result_processor)
+    }
+
+    fn register_retrying_result_processor(
+        self,
+        app: &mut App,
+        policy: RetryPolicy,
+    ) -> ScheduleConfigs<ScheduleSystem> {
+        self.register_with(app, |step, app| {
+            step.register_retrying_result_processor(app, policy)
+        })
+    }
+}
+
+impl<S, M> EventHandler<ConditionedM<M>> for Conditioned<S>
+where
+    S: EventHandler<M>,
+{
+    type In = S::In;
+
+    fn register_handler(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        self.register_with(app, S::register_handler)
+    }
+}
+
+/// This trait provides the `saga_run_if` method on processors, handlers and sagas.
+///
+/// A step with a run condition only runs when the condition is met at the moment the step would
+/// run. The condition is evaluated once for all events of the same type that are processed in an
+/// update, and the result is shared by every step and saga instance that processes them. When the
+/// condition is not met, the [ConditionPolicy] decides what happens to the event.
+/// By default, the event is consumed. All processors and handlers of the step, including the
+/// siblings, share the condition. When a whole saga is given a run condition, all of its steps
+/// share it.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::{Res, Resource};
+/// use bevy_saga_impl::prelude::{ConditionPolicy, RunIfStage};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[derive(Resource)]
+/// struct Paused(bool);
+///
+/// #[saga_event]
+/// struct Attack;
+///
+/// #[saga_event]
+/// struct Damage;
+///
+/// fn attack(_: Attack) -> Damage { Damage }
+/// fn take_damage(_: Damage, /* other queries or resources */) { }
+///
+/// fn running(paused: Res<Paused>) -> bool {
+///     !paused.0
+/// }
+///
+/// # let mut app = App::new();
+/// # app.insert_resource(Paused(false));
+/// // Attacks are dropped while the game is paused.
+/// app.add_saga(Update, (attack, take_damage).saga_run_if(running));
+///
+/// // Damage is taken once the game is resumed.
+/// app.add_saga(Update, (attack, take_damage.saga_run_if_with(running, ConditionPolicy::Keep)));
+/// ```
+///
+/// The methods are prefixed with `saga_`, so they don't clash with the `run_if` method of Bevy's
+/// [IntoScheduleConfigs](bevy::prelude::IntoScheduleConfigs), which systems implement as well.
+pub trait RunIfStage: Sized {
+    /// Only runs the step when the condition is met, and consumes the event otherwise.
+    fn saga_run_if<M>(self, condition: impl Condition<M>) -> Conditioned<Self> {
+        self.saga_run_if_with(condition, ConditionPolicy::Consume)
+    }
+
+    /// Only runs the step when the condition is met. The policy decides what happens to the event
+    /// otherwise.
+    fn saga_run_if_with<M>(
+        self,
+        condition: impl Condition<M>,
+        policy: ConditionPolicy,
+    ) -> Conditioned<Self>;
+}
+
+impl<S> RunIfStage for S {
+    fn saga_run_if_with<M>(
+        self,
+        condition: impl Condition<M>,
+        policy: ConditionPolicy,
+    ) -> Conditioned<Self> {
+        Conditioned {
+            step: self,
+            condition: Box::new(IntoSystem::into_system(condition)),
+            policy,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::condition::{ConditionPolicy, Conditioned, RunIfStage};
+use bevy::ecs::error::{BevyError, ErrorContext};
+use bevy::ecs::intern::Interned;
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::{Condition, SystemSet};
+
+/// Handles the errors of the steps of a saga, like Bevy's
+/// [error handlers](bevy::ecs::error::warn).
+pub type SagaErrorHandler = fn(BevyError, ErrorContext);
+
+/// Describes how a saga is added to the app with
+/// [add_configured_saga](crate::SagaRegistry::add_configured_saga).
+///
+/// Besides the schedule, a saga can be given:
+///
+/// - a name, which is used in the [SagaGraph](crate::prelude::SagaGraph) exports, in the lifecycle
+///   events like [SagaCompleted](crate::prelude::SagaCompleted) and in the errors of its steps.
+///   Sagas without a name are called by their id, like `saga 0`.
+/// - system sets, to order the systems of the saga relative to your own systems.
+/// - a run condition that all of its steps share, like [saga_run_if](crate::prelude::RunIfStage) on
+///   the whole saga.
+/// - an error handler for the errors of its steps, for example when a step is removed while its
+///   events are still propagated. By default, the errors are passed on to Bevy's error handler.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::ecs::error::warn;
+/// # use bevy::prelude::{Res, Resource, SystemSet};
+/// use bevy_saga_impl::prelude::SagaConfig;
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+/// struct CombatSet;
+///
+/// #[derive(Resource)]
+/// struct Paused(bool);
+///
+/// #[saga_event]
+/// struct Attack;
+///
+/// #[saga_event]
+/// struct Damage;
+///
+/// fn attack(_: Attack) -> Damage { Damage }
+/// fn take_damage(_: Damage, /* other queries or resources */) { }
+///
+/// fn running(paused: Res<Paused>) -> bool {
+///     !paused.0
+/// }
+///
+/// # let mut app = App::new();
+/// # app.insert_resource(Paused(false));
+/// app.add_configured_saga(
+///     SagaConfig::new(Update)
+///         .named("attack")
+///         .in_set(CombatSet)
+///         .run_if(running)
+///         .on_error(warn),
+///     (attack, take_damage),
+/// );
+/// ```
+pub struct SagaConfig {
+    pub(crate) label: Interned<dyn ScheduleLabel>,
+    pub(crate) name: Option<String>,
+    pub(crate) sets: Vec<Interned<dyn SystemSet>>,
+    pub(crate) condition: Option<Conditioned<()>>,
+    pub(crate) error_handler: Option<SagaErrorHandler>,
+}
+
+impl SagaConfig {
+    /// A saga that is added to the schedule under the label.
+    pub fn new(label: impl ScheduleLabel) -> Self {
+        SagaConfig {
+            label: label.intern(),
+            name: None,
+            sets: vec![],
+            condition: None,
+            error_handler: None,
+        }
+    }
+
+    /// Names the saga.
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Adds the systems of the saga to the set.
+    pub fn in_set(mut self, set: impl SystemSet) -> Self {
+        self.sets.push(set.intern());
+        self
+    }
+
+    /// Only runs the steps of the saga when the condition is met, and consumes their events
+    /// otherwise.
+    pub fn run_if<M>(self, condition: impl Condition<M>) -> Self {
+        self.run_if_with(condition, ConditionPolicy::Consume)
+    }
+
+    /// Only runs the steps of the saga when the condition is met. The policy decides what happens
+    /// to their events otherwise.
+    pub fn run_if_with<M>(mut self, condition: impl Condition<M>, policy: ConditionPolicy) -> Self {
+        self.condition = Some(().saga_run_if_with(condition, policy));
+        self
+    }
+
+    /// Handles the errors of the steps of the saga with the handler, instead of Bevy's error
+    /// handler.
+    pub fn on_error(mut self, handler: SagaErrorHandler) -> Self {
+        self.error_handler = Some(handler);
+        self
+    }
+}
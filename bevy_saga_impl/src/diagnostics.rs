@@ -0,0 +1,174 @@
+use crate::handle::SagaStates;
+use crate::instance::SagaInstanceId;
+use bevy::app::{App, Last, Plugin};
+use bevy::diagnostic::{Diagnostic, DiagnosticMeasurement, DiagnosticPath, DiagnosticsStore};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::platform::time::Instant;
+use bevy::prelude::{ResMut, Resource, World};
+use std::sync::Arc;
+
+/// A measurement the [SagaDiagnosticsPlugin] records for every step of a saga.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SagaStepMetric {
+    /// The number of events the step processed in the frame.
+    Events,
+    /// The number of Ok values a [result processor](crate::prelude::OkStage) returned in the frame.
+    Ok,
+    /// The number of Err values a [result processor](crate::prelude::ErrStage) returned in the
+    /// frame.
+    Err,
+    /// The number of events an [Option processor](crate::prelude::EventProcessor#option-processor)
+    /// or [iterator processor](crate::prelude::EventProcessor#iterator-processor) dropped in the
+    /// frame, because it returned None or no items.
+    Dropped,
+    /// The time the step took to run in the frame, in milliseconds.
+    Time,
+}
+
+impl SagaStepMetric {
+    fn as_str(self) -> &'static str {
+        match self {
+            SagaStepMetric::Events => "events",
+            SagaStepMetric::Ok => "ok",
+            SagaStepMetric::Err => "err",
+            SagaStepMetric::Dropped => "dropped",
+            SagaStepMetric::Time => "time",
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            SagaStepMetric::Time => "ms",
+            _ => "",
+        }
+    }
+}
+
+/// A plugin that records diagnostics of the steps of all sagas in the
+/// [DiagnosticsStore](DiagnosticsStore), so they are reported by plugins like Bevy's
+/// [LogDiagnosticsPlugin](bevy::diagnostic::LogDiagnosticsPlugin).
+///
+/// Every step gets one diagnostic per [metric](SagaStepMetric), under the path
+/// `saga/<saga name>/<step name>/<metric>`, see [path](SagaDiagnosticsPlugin::path). The names are the ones in the
+/// [SagaGraph](crate::prelude::SagaGraph). A diagnostic is added when the step first records the
+/// metric, and it gets a measurement in every frame from then on.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::diagnostic::DiagnosticsStore;
+/// use bevy_saga_impl::prelude::{SagaDiagnosticsPlugin, SagaStepMetric};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Attack;
+///
+/// fn attack(_: Attack, /* other queries or resources */) { }
+///
+/// # let mut app = App::new();
+/// app.add_plugins(SagaDiagnosticsPlugin);
+/// app.add_named_saga("attack", Update, attack);
+/// app.world_mut().send_event(Attack);
+/// app.update();
+///
+/// let path = SagaDiagnosticsPlugin::path("attack", "attack", SagaStepMetric::Events);
+/// let store = app.world().resource::<DiagnosticsStore>();
+/// assert_eq!(store.get_measurement(&path).map(|events| events.value), Some(1.0));
+/// ```
+pub struct SagaDiagnosticsPlugin;
+
+impl SagaDiagnosticsPlugin {
+    /// The path of the diagnostic of a metric of a step.
+    ///
+    /// Every `/` in the names of the saga and the step is replaced with `_`, and empty names become
+    /// `_`, so every name is one component of the path.
+    pub fn path(saga: &str, step: &str, metric: SagaStepMetric) -> DiagnosticPath {
+        let (saga, step) = (path_component(saga), path_component(step));
+        DiagnosticPath::from_components(["saga", &saga, &step, metric.as_str()])
+    }
+}
+
+fn path_component(name: &str) -> String {
+    if name.is_empty() {
+        "_".to_string()
+    } else {
+        name.replace('/', "_")
+    }
+}
+
+impl Plugin for SagaDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DiagnosticsStore>();
+        app.init_resource::<SagaDiagnostics>();
+        app.add_systems(Last, record_diagnostics);
+    }
+}
+
+/// A resource used by bevy_saga to count the metrics of the steps in the current frame.
+///
+/// It's not recommended to use this resource in your own code. Read the diagnostics from the
+/// [DiagnosticsStore] instead.
+#[derive(Resource, Default)]
+pub struct SagaDiagnostics {
+    /// The saga and the name of the step that is running.
+    current: Option<(Arc<str>, Arc<str>)>,
+    frame: HashMap<(Arc<str>, Arc<str>, SagaStepMetric), f64>,
+    known: HashSet<(Arc<str>, Arc<str>, SagaStepMetric)>,
+}
+
+impl SagaDiagnostics {
+    /// Adds to the metric of the step that is running.
+    pub(crate) fn count(&mut self, metric: SagaStepMetric) {
+        if let Some((saga, step)) = self.current.clone() {
+            self.add(saga, step, metric, 1.0);
+        }
+    }
+
+    fn add(&mut self, saga: Arc<str>, step: Arc<str>, metric: SagaStepMetric, value: f64) {
+        *self.frame.entry((saga, step, metric)).or_default() += value;
+    }
+}
+
+/// Runs a step of a saga, and counts its event and measures its time if the
+/// [SagaDiagnosticsPlugin] was added.
+pub(crate) fn measure_step<T>(
+    world: &mut World,
+    instance: SagaInstanceId,
+    step: Option<&Arc<str>>,
+    run: impl FnOnce(&mut World) -> T,
+) -> T {
+    let (Some(step), true) = (step, world.contains_resource::<SagaDiagnostics>()) else {
+        return run(world);
+    };
+    let saga = world.resource::<SagaStates>().name(instance.saga());
+    let previous = world
+        .resource_mut::<SagaDiagnostics>()
+        .current
+        .replace((saga.clone(), step.clone()));
+    let start = Instant::now();
+    let result = run(world);
+    let elapsed = start.elapsed();
+    let mut diagnostics = world.resource_mut::<SagaDiagnostics>();
+    diagnostics.current = previous;
+    diagnostics.add(saga.clone(), step.clone(), SagaStepMetric::Events, 1.0);
+    diagnostics.add(saga, step.clone(), SagaStepMetric::Time, elapsed.as_secs_f64() * 1000.0);
+    result
+}
+
+/// Adds the counts of the frame to the [DiagnosticsStore], and starts counting the next frame.
+fn record_diagnostics(mut diagnostics: ResMut<SagaDiagnostics>, mut store: ResMut<DiagnosticsStore>) {
+    let SagaDiagnostics { frame, known, .. } = &mut *diagnostics;
+    known.extend(frame.keys().cloned());
+    let time = Instant::now();
+    for key in known.iter() {
+        let (saga, step, metric) = key;
+        let path = SagaDiagnosticsPlugin::path(saga, step, *metric);
+        if store.get(&path).is_none() {
+            store.add(Diagnostic::new(path.clone()).with_suffix(metric.suffix()));
+        }
+        let value = frame.get(key).copied().unwrap_or_default();
+        if let Some(diagnostic) = store.get_mut(&path) {
+            diagnostic.add_measurement(DiagnosticMeasurement { time, value });
+        }
+    }
+    frame.clear();
+}
@@ -1,9 +1,32 @@
 use crate::SagaEvent;
+use crate::async_processor::{PendingTasks, poll_tasks, spawn_task};
+use crate::compensation::Compensations;
+use crate::condition::{ConditionalStep, HeldEvents, StepCondition, run_held_events};
+use crate::config::SagaConfig;
+use crate::graph::{SagaEdgeLabel, SagaGraph, SagaStepId, short_name};
+use crate::handle::{SagaHandle, SagaStates, remove_processors};
+use crate::instance::{
+    CurrentSagaInstance, EventInstances, SagaCancelled, SagaId, SagaInstanceId, SagaInstances,
+    StepSystem,
+};
+use crate::lifecycle::{SagaCompleted, SagaFailed, SagaStarted, SagaStepCompleted};
+use crate::retry::{PendingRetries, RetryPolicy, RetryingStep};
 use crate::saga::Saga;
-use crate::util::{EventProcessors, process_event, send_option_response, send_response, send_result_response};
+use crate::trace::traced;
+use crate::util::{
+    EventProcessors, SagaEventSet, SagaFlowSet, SharedEvents, StepFeed, process_event,
+    send_iter_response, send_option_response, send_response, send_result_response,
+};
+use bevy::ecs::error::BevyError;
+use bevy::ecs::intern::Interned;
 use bevy::ecs::schedule::{ScheduleConfigs, ScheduleLabel};
-use bevy::ecs::system::ScheduleSystem;
-use bevy::prelude::{App, Event, IntoScheduleConfigs, IntoSystem};
+use bevy::ecs::system::{ScheduleSystem, System, SystemId, SystemInput};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{
+    App, Event, IntoScheduleConfigs, IntoSystem, Res, ResMut, Resource, SystemSet, World,
+};
+use std::any::TypeId;
+use std::sync::Arc;
 
 /// The extension trait where sagas are added to the bevy App.
 /// 
@@ -16,22 +39,245 @@ use bevy::prelude::{App, Event, IntoScheduleConfigs, IntoSystem};
 /// If multiple sagas are registered under the same label, they will be executed concurrently.
 /// To order sagas in reference to each other, we recommend to add extra 
 /// [ScheduleLabels](ScheduleLabel).
+///
+/// Sagas under the same label that use the same event type share one
+/// [SagaEventSet](crate::prelude::SagaEventSet) for that type. Within a saga, the events are
+/// propagated after the processors that produce them. Different sagas may use the same event types
+/// in a different order. An event that is sent from the outside starts an instance of every saga
+/// that handles it. An event that is produced by a saga only continues in that saga, so every
+/// processor of the event runs exactly once per event.
 /// 
 /// Learn how to write a saga [here](Saga).
 pub trait SagaRegistry {
-    fn add_saga<M, L>(&mut self, label: L, saga: impl Saga<M>) -> &mut Self
+    /// Adds the saga to the schedule under the label.
+    ///
+    /// The returned [SagaHandle] can be used to [disable, enable or remove](crate::prelude::SagaControl)
+    /// the saga later on.
+    fn add_saga<M, L>(&mut self, label: L, saga: impl Saga<M>) -> SagaHandle
     where
         L: ScheduleLabel + Clone;
+
+    /// Adds the saga to the schedule under the label, and names it.
+    ///
+    /// The name is used in the [SagaGraph] exports, in the lifecycle events like
+    /// [SagaCompleted](crate::prelude::SagaCompleted) and in the errors of its steps.
+    ///
+    /// ```
+    /// # use bevy::app::{App, Update};
+    /// use bevy_saga_impl::prelude::SagaGraph;
+    /// # use bevy_saga_impl::SagaRegistry;
+    /// # use bevy_saga_macros::saga_event;
+    /// #[saga_event]
+    /// struct Attack;
+    ///
+    /// fn attack(_: Attack, /* other queries or resources */) { }
+    ///
+    /// # let mut app = App::new();
+    /// app.add_named_saga("attack", Update, attack);
+    ///
+    /// let mermaid = app.world().resource::<SagaGraph>().to_mermaid();
+    /// assert!(mermaid.contains("subgraph saga_0 [\"attack\"]"));
+    /// ```
+    fn add_named_saga<M, L>(&mut self, name: &str, label: L, saga: impl Saga<M>) -> SagaHandle
+    where
+        L: ScheduleLabel;
+
+    /// Adds the saga as described by the [SagaConfig].
+    fn add_configured_saga<M>(&mut self, config: SagaConfig, saga: impl Saga<M>) -> SagaHandle;
+
+    /// Lets sagas read events of type `R` without draining them, so ordinary
+    /// [EventReaders](bevy::prelude::EventReader) can read them as well.
+    ///
+    /// By default, sagas drain the events they process from the [Events](bevy::prelude::Events)
+    /// buffer. Once an event type is shared, the sagas keep one cursor for that type instead. The
+    /// events stay in the buffer until Bevy clears it, and every event is still propagated through
+    /// the sagas only once.
+    ///
+    /// ```
+    /// # use bevy::app::{App, PostUpdate, Update};
+    /// # use bevy::prelude::EventReader;
+    /// use bevy_saga_impl::SagaRegistry;
+    /// # use bevy_saga_macros::saga_event;
+    /// #[saga_event]
+    /// struct Damage(u8);
+    ///
+    /// fn take_damage(_: Damage, /* other queries or resources */) { }
+    ///
+    /// fn play_hit_sound(mut damage: EventReader<Damage>) {
+    ///     for _ in damage.read() { }
+    /// }
+    ///
+    /// # let mut app = App::new();
+    /// app.share_saga_events::<Damage>()
+    ///     .add_systems(PostUpdate, play_hit_sound);
+    /// app.add_saga(Update, take_damage);
+    /// ```
+    fn share_saga_events<R>(&mut self) -> &mut Self
+    where
+        R: SagaEvent;
 }
 
 impl SagaRegistry for App {
-    fn add_saga<M, L>(&mut self, label: L, saga: impl Saga<M>) -> &mut Self
+    fn add_saga<M, L>(&mut self, label: L, saga: impl Saga<M>) -> SagaHandle
     where
         L: ScheduleLabel + Clone,
     {
+        self.add_configured_saga(SagaConfig::new(label), saga)
+    }
+
+    fn add_named_saga<M, L>(&mut self, name: &str, label: L, saga: impl Saga<M>) -> SagaHandle
+    where
+        L: ScheduleLabel,
+    {
+        self.add_configured_saga(SagaConfig::new(label).named(name), saga)
+    }
+
+    fn add_configured_saga<M>(&mut self, config: SagaConfig, saga: impl Saga<M>) -> SagaHandle {
+        let SagaConfig {
+            label,
+            name,
+            sets,
+            condition,
+            error_handler,
+        } = config;
+        self.init_resource::<SagaRegistrations>();
+        self.init_resource::<SagaStates>();
+        self.init_resource::<SagaGraph>();
+        let id = self.world_mut().resource_mut::<SagaRegistrations>().begin();
+        if let Some(name) = name {
+            self.world_mut().resource_mut::<SagaGraph>().name_saga(id, &name);
+            self.world_mut().resource_mut::<SagaStates>().name_saga(id, &name);
+        }
+        if let Some(handler) = error_handler {
+            self.world_mut()
+                .resource_mut::<SagaStates>()
+                .set_error_handler(id, handler);
+        }
         // TODO: register is visible to everything that knows Saga.
-        let schedules = saga.register(self);
-        self.add_systems(label, schedules)
+        let mut schedules = match condition {
+            Some(condition) => condition.register_with(self, |(), app| saga.register(app)),
+            None => saga.register(self),
+        };
+        let orders = self.world_mut().resource_mut::<SagaRegistrations>().end();
+        for set in sets {
+            schedules = schedules.in_set(set);
+        }
+        self.add_systems(
+            label,
+            schedules.run_if(move |states: Res<SagaStates>| !states.is_removed(id)),
+        );
+        for (before, after) in orders {
+            self.configure_sets(label, before.before(after));
+        }
+        SagaHandle::new(id)
+    }
+
+    fn share_saga_events<R>(&mut self) -> &mut Self
+    where
+        R: SagaEvent,
+    {
+        self.add_event::<R>();
+        self.init_resource::<SharedEvents<R>>()
+    }
+}
+
+/// A resource used by bevy_saga to remember which saga is being added and how the events of that
+/// saga flow into each other.
+///
+/// It's not recommended to use this resource in your own code.
+#[derive(Resource, Default)]
+pub struct SagaRegistrations {
+    next: u64,
+    current: Option<SagaId>,
+    orders: Vec<(Interned<dyn SystemSet>, Interned<dyn SystemSet>)>,
+    conditions: Vec<StepCondition>,
+    next_inline: u64,
+    feeds: HashMap<TypeId, StepFeed>,
+    recorded: HashMap<TypeId, RecordedStep>,
+}
+
+/// A step that was recorded in the [SagaGraph] and waits for its system to be added.
+struct RecordedStep {
+    name: Arc<str>,
+    outputs: Vec<TypeId>,
+}
+
+impl SagaRegistrations {
+    fn begin(&mut self) -> SagaId {
+        let saga = SagaId(self.next);
+        self.next += 1;
+        self.current = Some(saga);
+        self.feeds.clear();
+        self.recorded.clear();
+        saga
+    }
+
+    /// Applies the run condition to all processors and handlers that are added until it is popped.
+    pub(crate) fn push_condition(&mut self, condition: StepCondition) {
+        self.conditions.push(condition);
+    }
+
+    pub(crate) fn pop_condition(&mut self) {
+        self.conditions.pop();
+    }
+
+    /// Orders the propagation of `R` before the propagation of `Rs` in the saga that is being
+    /// added, because `R` produces `Rs`.
+    pub(crate) fn order<R, Rs>(&mut self)
+    where
+        R: Event,
+        Rs: Event,
+    {
+        let saga = self.current();
+        self.orders.push((
+            SagaFlowSet::of::<R>(saga).intern(),
+            SagaFlowSet::of::<Rs>(saga).intern(),
+        ));
+    }
+
+    /// Hands out the id of a new lightweight step.
+    pub(crate) fn next_inline(&mut self) -> u64 {
+        let inline = self.next_inline;
+        self.next_inline += 1;
+        inline
+    }
+
+    /// Feeds the processors and handlers of `R` that are added next from `feed`.
+    pub(crate) fn set_feed<R>(&mut self, feed: StepFeed)
+    where
+        R: Event,
+    {
+        self.feeds.insert(TypeId::of::<R>(), feed);
+    }
+
+    /// The feeds of the processors and handlers that are added next, to restore them after adding
+    /// parallel branches of a saga.
+    pub(crate) fn feeds(&self) -> HashMap<TypeId, StepFeed> {
+        self.feeds.clone()
+    }
+
+    pub(crate) fn restore_feeds(&mut self, feeds: HashMap<TypeId, StepFeed>) {
+        self.feeds = feeds;
+    }
+
+    fn end(&mut self) -> Vec<(Interned<dyn SystemSet>, Interned<dyn SystemSet>)> {
+        self.current = None;
+        self.feeds.clear();
+        self.recorded.clear();
+        std::mem::take(&mut self.orders)
+    }
+
+    /// The saga that is being added. Processors that are added outside of
+    /// [add_saga](SagaRegistry::add_saga) form a saga of their own.
+    pub(crate) fn current(&mut self) -> SagaId {
+        match self.current {
+            Some(saga) => saga,
+            None => {
+                let saga = self.begin();
+                self.current = None;
+                saga
+            }
+        }
     }
 }
 
@@ -57,6 +303,24 @@ pub trait BevySagaUtil {
         R: SagaEvent,
         Rs: Event;
 
+    fn add_iter_processor<R, I, M>(
+        &mut self,
+        handler: impl IntoSystem<R, I, M> + 'static,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+        I: IntoIterator + 'static,
+        I::Item: Event;
+
+    fn add_async_processor<R, Fut, M>(
+        &mut self,
+        handler: impl IntoSystem<R, Fut, M> + 'static,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+        Fut: Future + Send + 'static,
+        Fut::Output: Event;
+
     fn add_result_handler<R, Ok, Err, M>(
         &mut self,
         handler: impl IntoSystem<R, Result<Ok, Err>, M> + 'static,
@@ -66,12 +330,55 @@ pub trait BevySagaUtil {
         Ok: Event,
         Err: Event;
 
+    fn add_retrying_result_handler<R, Ok, Err, M>(
+        &mut self,
+        handler: impl IntoSystem<R, Result<Ok, Err>, M> + 'static,
+        policy: RetryPolicy,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+        Ok: Event,
+        Err: Event;
+
     fn add_event_handler<R, M>(
         &mut self,
         handler: impl IntoSystem<R, (), M> + 'static,
     ) -> ScheduleConfigs<ScheduleSystem>
     where
         R: SagaEvent;
+
+    fn add_inline_step<R, Rs>(
+        &mut self,
+        name: &str,
+        step: impl Fn(R) -> Option<Rs> + Send + Sync + 'static,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+        Rs: SagaEvent;
+
+    fn add_compensation<R, M>(
+        &mut self,
+        undo: impl IntoSystem<R, (), M> + 'static,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent;
+
+    fn add_step_handler<R, M>(
+        &mut self,
+        handler: impl IntoSystem<R, (), M> + 'static,
+        label: Option<SagaEdgeLabel>,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent;
+
+    fn record_saga_step<R>(&mut self, name: &str, label: Option<SagaEdgeLabel>) -> SagaStepId
+    where
+        R: Event;
+
+    fn record_saga_flow<R, Rs>(&mut self, step: SagaStepId, label: Option<SagaEdgeLabel>)
+    where
+        R: SagaEvent,
+        Rs: Event;
 }
 
 impl BevySagaUtil for App {
@@ -83,13 +390,10 @@ impl BevySagaUtil for App {
         R: SagaEvent,
         Rs: Event,
     {
-        self.add_event::<R>();
-        self.init_resource::<EventProcessors<R>>();
-        let id = self.register_system(handler.pipe(send_response::<Rs>));
-        self.world_mut()
-            .resource_mut::<EventProcessors<R>>()
-            .push(id);
-        process_event::<R>.into_configs()
+        let handler = traced(IntoSystem::into_system(handler));
+        let step = self.record_saga_step::<R>(&handler.name(), None);
+        self.record_saga_flow::<R, Rs>(step, None);
+        self.add_event_handler(handler.pipe(send_response::<Rs>))
     }
 
     fn add_option_processor<R, Rs, M>(
@@ -100,13 +404,49 @@ impl BevySagaUtil for App {
         R: SagaEvent,
         Rs: Event,
     {
-        self.add_event::<R>();
-        self.init_resource::<EventProcessors<R>>();
-        let id = self.register_system(handler.pipe(send_option_response::<Rs>));
+        let handler = traced(IntoSystem::into_system(handler));
+        let step = self.record_saga_step::<R>(&handler.name(), None);
+        self.record_saga_flow::<R, Rs>(step, None);
+        self.add_event_handler(handler.pipe(send_option_response::<Rs>))
+    }
+
+    fn add_iter_processor<R, I, M>(
+        &mut self,
+        handler: impl IntoSystem<R, I, M> + 'static,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+        I: IntoIterator + 'static,
+        I::Item: Event,
+    {
+        let handler = traced(IntoSystem::into_system(handler));
+        let step = self.record_saga_step::<R>(&handler.name(), None);
+        self.record_saga_flow::<R, I::Item>(step, None);
+        self.add_event_handler(handler.pipe(send_iter_response::<I>))
+    }
+
+    fn add_async_processor<R, Fut, M>(
+        &mut self,
+        handler: impl IntoSystem<R, Fut, M> + 'static,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+        Fut: Future + Send + 'static,
+        Fut::Output: Event,
+    {
+        self.init_resource::<PendingTasks<Fut::Output>>();
+        let handler = traced(IntoSystem::into_system(handler));
+        let step = self.record_saga_step::<R>(&handler.name(), None);
+        self.record_saga_flow::<R, Fut::Output>(step, None);
+        let spawn = self.add_event_handler(handler.pipe(spawn_task::<Fut>));
         self.world_mut()
-            .resource_mut::<EventProcessors<R>>()
-            .push(id);
-        process_event::<R>.into_configs()
+            .resource_mut::<SagaInstances>()
+            .register_store::<PendingTasks<Fut::Output>>();
+        (
+            spawn,
+            in_saga_event_sets::<R, _>(self, poll_tasks::<Fut::Output>),
+        )
+            .chain()
     }
 
     fn add_result_handler<R, Ok, Err, M>(
@@ -118,11 +458,59 @@ impl BevySagaUtil for App {
         Ok: Event,
         Err: Event,
     {
-        self.add_event::<R>();
-        self.init_resource::<EventProcessors<R>>();
-        let id = self.register_system(handler.pipe(send_result_response::<Ok, Err>));
-        self.world_mut().resource_mut::<EventProcessors<R>>().push(id);
-        process_event::<R>.into_configs()
+        let handler = traced(IntoSystem::into_system(handler));
+        let step = self.record_saga_step::<R>(&handler.name(), None);
+        self.record_saga_flow::<R, Ok>(step, Some(SagaEdgeLabel::Ok));
+        self.record_saga_flow::<R, Err>(step, Some(SagaEdgeLabel::Err));
+        self.add_event_handler(handler.pipe(send_result_response::<Ok, Err>))
+    }
+
+    fn add_retrying_result_handler<R, Ok, Err, M>(
+        &mut self,
+        handler: impl IntoSystem<R, Result<Ok, Err>, M> + 'static,
+        policy: RetryPolicy,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+        Ok: Event,
+        Err: Event,
+    {
+        self.init_resource::<PendingRetries<R>>();
+        let handler = traced(IntoSystem::into_system(handler));
+        let graph_step = self.record_saga_step::<R>(&handler.name(), None);
+        self.record_saga_flow::<R, Ok>(graph_step, Some(SagaEdgeLabel::Ok));
+        self.record_saga_flow::<R, Err>(graph_step, Some(SagaEdgeLabel::Err));
+        let attempt = self.register_system(handler);
+        let send = self.register_system(send_result_response::<Ok, Err>);
+        let step = RetryingStep {
+            attempt: unregister_on_remove(self, attempt),
+            send: unregister_on_remove(self, send),
+            policy,
+        };
+        let first_attempt = add_step_system(
+            self,
+            StepSystem::Inline(Arc::new(move |world: &mut World, event: R| {
+                step.run(world, event, policy.times)
+            })),
+        );
+        // The retries run as the same step, with its run conditions, measurements and lifecycle
+        // events.
+        let template = self
+            .world()
+            .resource::<EventProcessors<R>>()
+            .last()
+            .cloned()
+            .expect("The first attempt was just added.");
+        self.world_mut()
+            .resource_mut::<SagaInstances>()
+            .register_store::<PendingRetries<R>>();
+        (
+            in_saga_event_sets::<R, _>(self, move |world: &mut World| {
+                step.retry_due(world, &template)
+            }),
+            first_attempt,
+        )
+            .chain()
     }
 
     fn add_event_handler<R, M>(
@@ -132,10 +520,288 @@ impl BevySagaUtil for App {
     where
         R: SagaEvent,
     {
-        self.add_event::<R>();
-        self.init_resource::<EventProcessors<R>>();
-        let id = self.register_system(handler);
-        self.world_mut().resource_mut::<EventProcessors<R>>().push(id);
-        process_event::<R>.into_configs()
+        let system = StepSystem::Registered(self.register_system(handler));
+        add_step_system(self, system)
+    }
+
+    fn add_inline_step<R, Rs>(
+        &mut self,
+        name: &str,
+        step: impl Fn(R) -> Option<Rs> + Send + Sync + 'static,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+        Rs: SagaEvent,
+    {
+        let graph_step = self.record_saga_step::<R>(name, None);
+        self.world_mut()
+            .resource_mut::<SagaGraph>()
+            .add_output(graph_step, std::any::type_name::<Rs>(), None);
+        let inline = self
+            .world_mut()
+            .resource_mut::<SagaRegistrations>()
+            .next_inline();
+        let configs = add_inline_system(self, inline, move |_: &mut World, event| step(event));
+        self.world_mut()
+            .resource_mut::<SagaRegistrations>()
+            .set_feed::<Rs>(StepFeed {
+                events: TypeId::of::<R>() != TypeId::of::<Rs>(),
+                inline: Some(inline),
+            });
+        configs
+    }
+
+    fn add_compensation<R, M>(
+        &mut self,
+        undo: impl IntoSystem<R, (), M> + 'static,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+    {
+        self.init_resource::<Compensations>();
+        let undo = self.register_system(undo);
+        let undo = unregister_on_remove(self, undo);
+        self.add_event_handler(
+            move |event: R, current: Res<CurrentSagaInstance>, mut compensations: ResMut<Compensations>| {
+                if let Some(instance) = current.instance() {
+                    compensations.record(instance, undo, event);
+                }
+            },
+        )
+    }
+
+    fn add_step_handler<R, M>(
+        &mut self,
+        handler: impl IntoSystem<R, (), M> + 'static,
+        label: Option<SagaEdgeLabel>,
+    ) -> ScheduleConfigs<ScheduleSystem>
+    where
+        R: SagaEvent,
+    {
+        let handler = traced(IntoSystem::into_system(handler));
+        self.record_saga_step::<R>(&handler.name(), label);
+        self.add_event_handler(handler)
+    }
+
+    fn record_saga_step<R>(&mut self, name: &str, label: Option<SagaEdgeLabel>) -> SagaStepId
+    where
+        R: Event,
+    {
+        self.init_resource::<SagaRegistrations>();
+        self.init_resource::<SagaGraph>();
+        let mut registrations = self.world_mut().resource_mut::<SagaRegistrations>();
+        let saga = registrations.current();
+        registrations.recorded.insert(
+            TypeId::of::<R>(),
+            RecordedStep {
+                name: Arc::from(short_name(name)),
+                outputs: vec![],
+            },
+        );
+        let mut graph = self.world_mut().resource_mut::<SagaGraph>();
+        let step = graph.add_step(saga, name);
+        graph.add_input(std::any::type_name::<R>(), step, label);
+        step
+    }
+
+    fn record_saga_flow<R, Rs>(&mut self, step: SagaStepId, label: Option<SagaEdgeLabel>)
+    where
+        R: SagaEvent,
+        Rs: Event,
+    {
+        self.init_resource::<SagaRegistrations>();
+        self.init_resource::<SagaGraph>();
+        self.world_mut()
+            .resource_mut::<SagaGraph>()
+            .add_output(step, std::any::type_name::<Rs>(), label);
+        let mut registrations = self.world_mut().resource_mut::<SagaRegistrations>();
+        registrations.order::<R, Rs>();
+        if let Some(recorded) = registrations.recorded.get_mut(&TypeId::of::<R>()) {
+            recorded.outputs.push(TypeId::of::<Rs>());
+        }
+    }
+}
+
+/// Adds a lightweight step to the saga that is being added. Its output is handed directly to the
+/// steps that are fed by `inline`.
+pub(crate) fn add_inline_system<R, Rs>(
+    app: &mut App,
+    inline: u64,
+    step: impl Fn(&mut World, R) -> Option<Rs> + Send + Sync + 'static,
+) -> ScheduleConfigs<ScheduleSystem>
+where
+    R: SagaEvent,
+    Rs: SagaEvent,
+{
+    add_inline_system_as(app, inline, move |world, event: R| {
+        let response = step(world, event)?;
+        Some((world.resource::<CurrentSagaInstance>().get()?, response))
+    })
+}
+
+/// Like [add_inline_system], but the step also picks the saga instance that the following steps
+/// run as.
+pub(crate) fn add_inline_system_as<R, Rs>(
+    app: &mut App,
+    inline: u64,
+    step: impl Fn(&mut World, R) -> Option<(SagaInstanceId, Rs)> + Send + Sync + 'static,
+) -> ScheduleConfigs<ScheduleSystem>
+where
+    R: SagaEvent,
+    Rs: SagaEvent,
+{
+    let system = StepSystem::Inline(Arc::new(move |world: &mut World, event: R| {
+        let Some((instance, response)) = step(world, event) else {
+            return Ok(());
+        };
+        run_inline(world, instance, inline, response)
+    }));
+    add_step_system(app, system)
+}
+
+/// Runs the steps that are fed by `inline` with the event, as the saga instance.
+pub(crate) fn run_inline<R>(
+    world: &mut World,
+    instance: SagaInstanceId,
+    inline: u64,
+    event: R,
+) -> Result<(), BevyError>
+where
+    R: SagaEvent,
+{
+    let following: Vec<_> = world
+        .get_resource::<EventProcessors<R>>()
+        .map(|processors| processors.fed_by(instance.saga(), inline).cloned().collect())
+        .unwrap_or_default();
+    for step in following {
+        step.run(instance, event.clone())(world)?;
+    }
+    Ok(())
+}
+
+/// Unregisters a system that helps the processors of the saga that is being added when the saga is
+/// removed.
+fn unregister_on_remove<I, O>(app: &mut App, system: SystemId<I, O>) -> SystemId<I, O>
+where
+    I: SystemInput + 'static,
+    O: 'static,
+{
+    app.init_resource::<SagaRegistrations>();
+    app.init_resource::<SagaStates>();
+    let saga = app.world_mut().resource_mut::<SagaRegistrations>().current();
+    app.world_mut()
+        .resource_mut::<SagaStates>()
+        .unregister_on_remove(saga, system);
+    system
+}
+
+/// Adds a processor or handler to the saga that is being added, and returns the system that
+/// propagates its input events.
+pub(crate) fn add_step_system<R>(app: &mut App, system: StepSystem<R>) -> ScheduleConfigs<ScheduleSystem>
+where
+    R: SagaEvent,
+{
+    init_saga_event::<R>(app);
+    let mut registrations = app.world_mut().resource_mut::<SagaRegistrations>();
+    let saga = registrations.current();
+    let conditions = registrations.conditions.clone();
+    let feed = registrations
+        .feeds
+        .get(&TypeId::of::<R>())
+        .copied()
+        .unwrap_or_default();
+    // The first system that is added for a recorded step reports it in the lifecycle events.
+    let (name, outputs) = match registrations.recorded.remove(&TypeId::of::<R>()) {
+        Some(RecordedStep { name, outputs }) => (Some(name), outputs),
+        None => (None, vec![]),
+    };
+    let step = ConditionalStep {
+        system,
+        conditions,
+        name,
+        outputs,
+    };
+    let keeps_events = step.keeps_events();
+    app.world_mut()
+        .resource_mut::<EventProcessors<R>>()
+        .push_step(saga, feed, step);
+    app.world_mut()
+        .resource_mut::<SagaStates>()
+        .on_remove(saga, remove_processors::<R>);
+    if !keeps_events {
+        return in_saga_event_sets::<R, _>(app, process_event::<R>);
+    }
+    app.init_resource::<HeldEvents<R>>();
+    app.world_mut()
+        .resource_mut::<SagaInstances>()
+        .register_store::<HeldEvents<R>>();
+    in_saga_event_sets::<R, _>(app, (run_held_events::<R>, process_event::<R>))
+}
+
+/// Puts systems that propagate the events of type `R` in the set that all sagas share, and in the
+/// set of the saga that is being added.
+pub(crate) fn in_saga_event_sets<R, M>(
+    app: &mut App,
+    systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+) -> ScheduleConfigs<ScheduleSystem>
+where
+    R: SagaEvent,
+{
+    let saga = app.world_mut().resource_mut::<SagaRegistrations>().current();
+    systems
+        .in_set(SagaEventSet::<R>::default())
+        .in_set(SagaFlowSet::of::<R>(saga))
+}
+
+/// Adds a system to the saga that is being added, which receives every event of type `R` without
+/// starting a saga instance, and returns the system that propagates the events.
+///
+/// Run conditions don't apply to the system, because it doesn't run on behalf of a saga instance.
+pub(crate) fn add_saga_consumer<R, M>(
+    app: &mut App,
+    consumer: impl IntoSystem<R, (), M> + 'static,
+) -> ScheduleConfigs<ScheduleSystem>
+where
+    R: SagaEvent,
+{
+    init_saga_event::<R>(app);
+    let consumer = app.register_system(consumer);
+    let saga = app.world_mut().resource_mut::<SagaRegistrations>().current();
+    app.world_mut()
+        .resource_mut::<EventProcessors<R>>()
+        .push_consumer(saga, consumer);
+    app.world_mut()
+        .resource_mut::<SagaStates>()
+        .on_remove(saga, remove_processors::<R>);
+    in_saga_event_sets::<R, _>(app, process_event::<R>)
+}
+
+/// Adds the event type `R` and the resources that propagate it through the sagas.
+fn init_saga_event<R>(app: &mut App)
+where
+    R: SagaEvent,
+{
+    app.add_event::<R>();
+    app.add_event::<SagaCancelled>();
+    app.add_event::<SagaStarted>();
+    app.add_event::<SagaStepCompleted>();
+    app.add_event::<SagaCompleted>();
+    app.add_event::<SagaFailed>();
+    app.init_resource::<EventProcessors<R>>();
+    app.init_resource::<EventInstances<R>>();
+    app.init_resource::<CurrentSagaInstance>();
+    app.init_resource::<SagaInstances>();
+    app.world_mut()
+        .resource_mut::<SagaInstances>()
+        .register_store::<EventInstances<R>>();
+    app.init_resource::<SagaRegistrations>();
+    app.init_resource::<SagaStates>();
+    #[cfg(feature = "serde")]
+    app.world_mut()
+        .get_resource_or_init::<crate::record::SagaReplayers>()
+        .register::<R>();
+    #[cfg(feature = "testing")]
+    if let Some(mut captured) = app.world_mut().get_resource_mut::<crate::testing::CapturedEvents>() {
+        captured.register::<R>();
     }
 }
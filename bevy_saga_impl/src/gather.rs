@@ -0,0 +1,136 @@
+use crate::branches::{Branches, Group};
+use crate::processor::EventProcessor;
+use crate::SagaEvent;
+use bevy::app::App;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::{Event, IntoScheduleConfigs, SystemInput};
+use variadics_please::all_tuples;
+
+/// The event that is propagated through a saga once all branches of a [gather] have replied.
+///
+/// It holds the replies in the order of the branches.
+#[derive(Clone)]
+pub struct Gathered<R>(pub Vec<R>);
+
+impl<R> Event for Gathered<R>
+where
+    R: SagaEvent,
+{
+    type Traversal = ();
+}
+
+impl<R> SystemInput for Gathered<R>
+where
+    R: SagaEvent,
+{
+    type Param<'i> = Gathered<R>;
+    type Inner<'i> = Gathered<R>;
+
+    fn wrap(this: Self::Inner<'_>) -> Self::Param<'_> {
+        this
+    }
+}
+
+impl<R> SagaEvent for Gathered<R> where R: SagaEvent {}
+
+pub struct Gather<T>(T);
+
+pub struct GatherM<T>(T);
+
+macro_rules! impl_gather {
+    ($(#[$meta:meta])* $(($B:ident, $b:ident, $M:ident)),*) => {
+        impl<$($B,)* $($M,)* In, Out> EventProcessor<GatherM<($($M,)*)>> for Gather<($($B,)*)>
+        where
+            In: SagaEvent,
+            Out: SagaEvent,
+            $($B: EventProcessor<$M, In = In, Out = Out>,)*
+        {
+            type In = In;
+            type Out = Gathered<Out>;
+
+            fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+                let Gather(($($b,)*)) = self;
+                let mut branches = Branches::<In, Out, Vec<Option<Out>>>::new(app);
+                let entered = branches.entered();
+                let gathering = ($(branches.add_branch(app, $b),)*).into_configs();
+                // Only the first reply of every branch is gathered. The last reply to an event
+                // passes all replies on.
+                let gate = branches.finish(app, Some("gather"), move |world, reply: Out| {
+                    let mut entered = entered.lock().unwrap();
+                    let (instance, branch, group) = entered.current(world)?;
+                    let Group { entries, state: replies } = entered.group_mut(group)?;
+                    replies.resize_with(entries.len(), || None);
+                    replies[branch].get_or_insert(reply);
+                    if replies.iter().any(Option::is_none) {
+                        return None;
+                    }
+                    let replies = entered.leave(group)?.state;
+                    Some((instance, Gathered(replies.into_iter().flatten().collect())))
+                });
+                (gate, gathering).into_configs()
+            }
+        }
+    }
+}
+
+all_tuples!(impl_gather, 1, 15, B, b, M);
+
+/// A saga step that sends its event to several branches and collects all of their replies into
+/// one [Gathered] event.
+///
+/// Every branch is an [event processor](EventProcessor) that takes the event as input and returns
+/// a reply of the same type as the other branches. Branches that suspend the saga instance, like
+/// [await_event](crate::prelude::await_event) or an
+/// [async processor](EventProcessor#async-processor), may reply several updates later. Once every
+/// branch replied, the replies are collected in the order of the branches and passed on to the
+/// following processors or handler. When a saga instance receives several events, for example
+/// from an [iterator processor](EventProcessor#iterator-processor), the replies to each of them
+/// are gathered separately.
+///
+/// Only the first reply of every branch is gathered. When a branch doesn't reply, for example
+/// because its run condition isn't met or it [timed out](crate::prelude::TimeoutStage), the
+/// event isn't gathered, and the saga instance finishes without running the following steps.
+/// To continue with the first of several branches instead, use [race](crate::prelude::race).
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::{Component, Query};
+/// use bevy_saga_impl::prelude::{gather, Gathered};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[derive(Component)]
+/// struct Helmet(u8);
+///
+/// #[derive(Component)]
+/// struct Chestplate(u8);
+///
+/// #[saga_event]
+/// struct Hit(u8);
+///
+/// #[saga_event]
+/// struct Reduction(u8);
+///
+/// #[saga_event]
+/// struct Damage(u8);
+///
+/// fn helmet(_: Hit, helmets: Query<&Helmet>) -> Reduction {
+///     Reduction(helmets.iter().map(|helmet| helmet.0).sum())
+/// }
+///
+/// fn chestplate(_: Hit, chestplates: Query<&Chestplate>) -> Reduction {
+///     Reduction(chestplates.iter().map(|chestplate| chestplate.0).sum())
+/// }
+///
+/// fn reduce(Gathered(reductions): Gathered<Reduction>) -> Damage {
+///     Damage(reductions.iter().map(|reduction| reduction.0).sum())
+/// }
+///
+/// fn take_damage(_: Damage, /* other queries or resources */) { }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, (gather((helmet, chestplate)), reduce, take_damage));
+/// ```
+pub fn gather<T>(branches: T) -> Gather<T> {
+    Gather(branches)
+}
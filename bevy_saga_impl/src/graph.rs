@@ -0,0 +1,264 @@
+use crate::instance::SagaId;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::Resource;
+use std::fmt::Write;
+
+/// The label of an edge in the [SagaGraph].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SagaEdgeLabel {
+    /// The Ok value of a result processor.
+    Ok,
+    /// The Err value of a result processor.
+    Err,
+    /// A variant of a `#[saga_router]` enum.
+    Variant(&'static str),
+    /// A sibling of an event processor, which receives the same event as the processor.
+    Sibling,
+}
+
+impl SagaEdgeLabel {
+    fn as_str(&self) -> &str {
+        match self {
+            SagaEdgeLabel::Ok => "ok",
+            SagaEdgeLabel::Err => "err",
+            SagaEdgeLabel::Variant(variant) => variant,
+            SagaEdgeLabel::Sibling => "sibling",
+        }
+    }
+}
+
+/// Identifies a step in the [SagaGraph].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SagaStepId(usize);
+
+/// A processor or handler in the [SagaGraph].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SagaStep {
+    /// The saga the step was added to.
+    pub saga: SagaId,
+    /// The name of the system of the step, without its module path.
+    pub name: String,
+}
+
+/// A node in the [SagaGraph].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SagaNode {
+    /// An event type, by its name without module paths.
+    Event(String),
+    Step(SagaStepId),
+}
+
+/// An edge in the [SagaGraph], from an event to the step that receives it, or from a step to the
+/// event it produces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SagaEdge {
+    pub from: SagaNode,
+    pub to: SagaNode,
+    pub label: Option<SagaEdgeLabel>,
+}
+
+/// A resource that records the shape of all sagas that are added to the app.
+///
+/// Event types are shared by all sagas, just like the sagas share the systems that propagate them.
+/// Steps belong to the saga they were added to. Export the graph with [to_dot](SagaGraph::to_dot)
+/// or [to_mermaid](SagaGraph::to_mermaid). Both exports list everything in the order the sagas were
+/// added, so they can be checked in and diffed. The sagas are labeled with their
+/// [name](crate::SagaRegistry::add_named_saga), or with their id if they have none.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// use bevy_saga_impl::prelude::SagaGraph;
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Attack;
+///
+/// #[saga_event]
+/// struct Damage;
+///
+/// fn attack(_: Attack) -> Damage { Damage }
+/// fn take_damage(_: Damage) { }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, (attack, take_damage));
+///
+/// let mermaid = app.world().resource::<SagaGraph>().to_mermaid();
+/// assert!(mermaid.contains("step_0[\"attack\"]"));
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct SagaGraph {
+    events: Vec<String>,
+    steps: Vec<SagaStep>,
+    edges: Vec<SagaEdge>,
+    names: HashMap<SagaId, String>,
+}
+
+impl SagaGraph {
+    /// All event types in the graph.
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+
+    /// All steps in the graph.
+    pub fn steps(&self) -> impl Iterator<Item = (SagaStepId, &SagaStep)> {
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| (SagaStepId(index), step))
+    }
+
+    /// All edges in the graph.
+    pub fn edges(&self) -> &[SagaEdge] {
+        &self.edges
+    }
+
+    /// The name of the saga. Sagas that were added without a
+    /// [name](crate::SagaRegistry::add_named_saga) are called by their id.
+    pub fn saga_name(&self, saga: SagaId) -> String {
+        match self.names.get(&saga) {
+            Some(name) => name.clone(),
+            None => saga.to_string(),
+        }
+    }
+
+    pub(crate) fn name_saga(&mut self, saga: SagaId, name: &str) {
+        self.names.insert(saga, name.to_string());
+    }
+
+    pub(crate) fn add_step(&mut self, saga: SagaId, name: &str) -> SagaStepId {
+        self.steps.push(SagaStep {
+            saga,
+            name: short_name(name),
+        });
+        SagaStepId(self.steps.len() - 1)
+    }
+
+    pub(crate) fn add_input(&mut self, event: &str, step: SagaStepId, label: Option<SagaEdgeLabel>) {
+        let event = self.add_event(event);
+        self.edges.push(SagaEdge {
+            from: event,
+            to: SagaNode::Step(step),
+            label,
+        });
+    }
+
+    pub(crate) fn add_output(&mut self, step: SagaStepId, event: &str, label: Option<SagaEdgeLabel>) {
+        let event = self.add_event(event);
+        self.edges.push(SagaEdge {
+            from: SagaNode::Step(step),
+            to: event,
+            label,
+        });
+    }
+
+    fn add_event(&mut self, event: &str) -> SagaNode {
+        let event = short_name(event);
+        if !self.events.contains(&event) {
+            self.events.push(event.clone());
+        }
+        SagaNode::Event(event)
+    }
+
+    fn node_id(&self, node: &SagaNode) -> String {
+        match node {
+            SagaNode::Event(event) => {
+                let index = self.events.iter().position(|known| known == event);
+                format!("event_{}", index.expect("Edges only refer to known events."))
+            }
+            SagaNode::Step(SagaStepId(index)) => format!("step_{index}"),
+        }
+    }
+
+    /// The sagas in the graph, in the order they were added.
+    fn sagas(&self) -> Vec<SagaId> {
+        let mut sagas: Vec<SagaId> = vec![];
+        for step in &self.steps {
+            if !sagas.contains(&step.saga) {
+                sagas.push(step.saga);
+            }
+        }
+        sagas
+    }
+
+    /// Exports the graph in the DOT language of [Graphviz](https://graphviz.org).
+    ///
+    /// Every saga is drawn as a cluster of its steps.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph sagas {\n");
+        for (index, event) in self.events.iter().enumerate() {
+            writeln!(dot, "    event_{index} [label=\"{}\", shape=ellipse];", escape(event)).unwrap();
+        }
+        for saga in self.sagas() {
+            writeln!(dot, "    subgraph cluster_saga_{} {{", saga.0).unwrap();
+            writeln!(dot, "        label=\"{}\";", escape(&self.saga_name(saga))).unwrap();
+            for (SagaStepId(index), step) in self.steps().filter(|(_, step)| step.saga == saga) {
+                writeln!(dot, "        step_{index} [label=\"{}\", shape=box];", escape(&step.name)).unwrap();
+            }
+            dot.push_str("    }\n");
+        }
+        for edge in &self.edges {
+            let (from, to) = (self.node_id(&edge.from), self.node_id(&edge.to));
+            match &edge.label {
+                Some(label) => writeln!(dot, "    {from} -> {to} [label=\"{}\"];", label.as_str()),
+                None => writeln!(dot, "    {from} -> {to};"),
+            }
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exports the graph as a [Mermaid](https://mermaid.js.org) flowchart.
+    ///
+    /// Every saga is drawn as a subgraph of its steps.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+        for (index, event) in self.events.iter().enumerate() {
+            writeln!(mermaid, "    event_{index}([\"{}\"])", escape_mermaid(event)).unwrap();
+        }
+        for saga in self.sagas() {
+            let name = escape_mermaid(&self.saga_name(saga));
+            writeln!(mermaid, "    subgraph saga_{} [\"{name}\"]", saga.0).unwrap();
+            for (SagaStepId(index), step) in self.steps().filter(|(_, step)| step.saga == saga) {
+                writeln!(mermaid, "        step_{index}[\"{}\"]", escape_mermaid(&step.name)).unwrap();
+            }
+            mermaid.push_str("    end\n");
+        }
+        for edge in &self.edges {
+            let (from, to) = (self.node_id(&edge.from), self.node_id(&edge.to));
+            match &edge.label {
+                Some(label) => writeln!(mermaid, "    {from} -->|{}| {to}", label.as_str()),
+                None => writeln!(mermaid, "    {from} --> {to}"),
+            }
+            .unwrap();
+        }
+        mermaid
+    }
+}
+
+/// Strips the module paths from a type or system name, also from its generic parameters.
+pub(crate) fn short_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (index, character) in name.char_indices() {
+        if matches!(character, '<' | '>' | '(' | ')' | '[' | ']' | ',' | ' ' | '&' | ';') {
+            short.push_str(last_segment(&name[segment_start..index]));
+            short.push(character);
+            segment_start = index + character.len_utf8();
+        }
+    }
+    short.push_str(last_segment(&name[segment_start..]));
+    short
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+fn escape(label: &str) -> String {
+    label.replace('"', "\\\"")
+}
+
+fn escape_mermaid(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
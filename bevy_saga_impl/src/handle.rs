@@ -0,0 +1,309 @@
+use crate::SagaEvent;
+use crate::compensation::Compensations;
+use crate::config::SagaErrorHandler;
+use crate::instance::{
+    SagaId, SagaInstanceId, SagaInstances, StepSystem, cancel_instance, own_instance,
+};
+use crate::util::EventProcessors;
+use bevy::ecs::system::{SystemId, SystemInput};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::{Commands, Entity, Resource, World};
+use std::sync::Arc;
+
+/// A handle to a saga, returned by [add_saga](crate::SagaRegistry::add_saga).
+///
+/// Pass the handle to the methods of [SagaControl] to disable, enable or remove the saga while the
+/// app is running.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SagaHandle {
+    saga: SagaId,
+}
+
+impl SagaHandle {
+    pub(crate) fn new(saga: SagaId) -> Self {
+        SagaHandle { saga }
+    }
+
+    /// The id of the saga.
+    pub fn id(&self) -> SagaId {
+        self.saga
+    }
+}
+
+/// Unregisters the processors of one event type of a saga.
+type Remover = fn(&mut World, SagaId);
+
+/// Unregisters a system that helps the processors of a saga, like a
+/// [compensation](crate::prelude::CompensateStage).
+type Unregister = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// A resource that knows which sagas are disabled or removed.
+#[derive(Resource, Default)]
+pub struct SagaStates {
+    disabled: HashSet<SagaId>,
+    removed: HashSet<SagaId>,
+    removers: Vec<(SagaId, Remover)>,
+    helpers: Vec<(SagaId, Unregister)>,
+    names: HashMap<SagaId, Arc<str>>,
+    error_handlers: HashMap<SagaId, SagaErrorHandler>,
+}
+
+impl SagaStates {
+    /// Returns true if the saga propagates events.
+    pub fn is_enabled(&self, saga: SagaId) -> bool {
+        !self.disabled.contains(&saga) && !self.removed.contains(&saga)
+    }
+
+    /// The name of the saga, as it appears in the lifecycle events like
+    /// [SagaCompleted](crate::prelude::SagaCompleted). Sagas that were added without a
+    /// [name](crate::SagaRegistry::add_named_saga) are called by their id.
+    pub fn name(&self, saga: SagaId) -> Arc<str> {
+        match self.names.get(&saga) {
+            Some(name) => name.clone(),
+            None => Arc::from(saga.to_string()),
+        }
+    }
+
+    pub(crate) fn name_saga(&mut self, saga: SagaId, name: &str) {
+        self.names.insert(saga, Arc::from(name));
+    }
+
+    /// The handler for the errors of the steps of the saga, if it was given one.
+    pub(crate) fn error_handler(&self, saga: SagaId) -> Option<SagaErrorHandler> {
+        self.error_handlers.get(&saga).copied()
+    }
+
+    pub(crate) fn set_error_handler(&mut self, saga: SagaId, handler: SagaErrorHandler) {
+        self.error_handlers.insert(saga, handler);
+    }
+
+    /// Returns true if the saga was removed.
+    pub fn is_removed(&self, saga: SagaId) -> bool {
+        self.removed.contains(&saga)
+    }
+
+    /// Remembers how to unregister the processors of the saga, for when it is removed.
+    pub(crate) fn on_remove(&mut self, saga: SagaId, remover: Remover) {
+        self.removers.push((saga, remover));
+    }
+
+    /// Remembers to unregister a system that helps the processors of the saga, for when it is
+    /// removed.
+    pub(crate) fn unregister_on_remove<I, O>(&mut self, saga: SagaId, system: SystemId<I, O>)
+    where
+        I: SystemInput + 'static,
+        O: 'static,
+    {
+        self.helpers.push((
+            saga,
+            Box::new(move |world| {
+                // A helper that removes its own saga is still running, so it can't be
+                // unregistered.
+                let _ = world.unregister_system(system);
+            }),
+        ));
+    }
+}
+
+pub(crate) fn remove_processors<R>(world: &mut World, saga: SagaId)
+where
+    R: SagaEvent,
+{
+    let steps = world.resource_mut::<EventProcessors<R>>().remove(saga);
+    for step in steps {
+        // A step that removes its own saga is still running, so it can't be unregistered. Steps
+        // also share their conditions, so those may have been unregistered already.
+        if let StepSystem::Registered(system) = step.system {
+            let _ = world.unregister_system(system);
+        }
+        for condition in step.condition_systems() {
+            let _ = world.unregister_system(condition);
+        }
+    }
+    let consumers = world.resource_mut::<EventProcessors<R>>().remove_consumers(saga);
+    for consumer in consumers {
+        let _ = world.unregister_system(consumer);
+    }
+}
+
+/// Disables, enables and removes sagas, and cancels saga instances, while the app is running.
+///
+/// A disabled saga doesn't start new saga instances. Events of instances that were already running
+/// are dropped at the next step of the saga, and the instances finish without running the
+/// remaining steps. Enabling the saga again lets it handle new events.
+///
+/// Removing a saga disables it for good. Its processors, handlers and the systems that help them,
+/// like compensations or the attempts of [retried](crate::prelude::RetryStage) steps, are
+/// unregistered.
+/// Pending retries and compensations of its instances are dropped. The systems it added to the
+/// schedule can't be removed from it, but a run condition skips them from then on, so the saga
+/// no longer handles any events.
+///
+/// Cancelling a saga instance stops it, whether its steps are running or it is suspended, for
+/// example by [await_event](crate::prelude::await_event). Its events are dropped and its remaining
+/// steps don't run. A [SagaCancelled](crate::prelude::SagaCancelled) event is sent for the
+/// instance. To cancel all instances that concern an entity, make the entity the owner of the
+/// instances first, or mark the entity field of the event that starts them with
+/// [#\[owner\]](crate::SagaEvent). Owned instances are cancelled when their owner is despawned.
+///
+/// Both [World] and [Commands] implement this trait.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::{Commands, Res, Resource};
+/// use bevy_saga_impl::prelude::{SagaControl, SagaHandle};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Attack;
+///
+/// #[saga_event]
+/// struct EnterPeacefulMode;
+///
+/// #[derive(Resource)]
+/// struct Combat(SagaHandle);
+///
+/// fn attack(_: Attack, /* other queries or resources */) { }
+///
+/// fn enter_peaceful_mode(_: EnterPeacefulMode, combat: Res<Combat>, mut commands: Commands) {
+///     commands.disable_saga(combat.0);
+/// }
+///
+/// # let mut app = App::new();
+/// let combat = app.add_saga(Update, attack);
+/// app.insert_resource(Combat(combat));
+/// app.add_saga(Update, enter_peaceful_mode);
+///
+/// // The World can control sagas as well.
+/// app.world_mut().remove_saga(combat);
+/// ```
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::{Commands, Entity};
+/// use bevy_saga_impl::prelude::{SagaControl, SagaInstance};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Attack(Entity);
+///
+/// #[saga_event]
+/// struct Stunned(Entity);
+///
+/// fn attack(Attack(attacker): Attack, instance: SagaInstance, mut commands: Commands) {
+///     commands.set_instance_owner(instance.id(), attacker);
+/// }
+///
+/// fn stun(Stunned(entity): Stunned, mut commands: Commands) {
+///     // Stops all attacks of the entity that are still running.
+///     commands.cancel_owned_instances(entity);
+/// }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, attack);
+/// app.add_saga(Update, stun);
+/// ```
+pub trait SagaControl {
+    /// Stops the saga from propagating events until it is enabled again.
+    fn disable_saga(&mut self, saga: SagaHandle);
+
+    /// Lets a disabled saga propagate events again. Removed sagas stay removed.
+    fn enable_saga(&mut self, saga: SagaHandle);
+
+    /// Removes the saga from the app.
+    fn remove_saga(&mut self, saga: SagaHandle);
+
+    /// Stops the saga instance and drops its events. Instances that already finished are not
+    /// cancelled, and no [SagaCancelled](crate::prelude::SagaCancelled) is sent for them.
+    fn cancel_instance(&mut self, instance: SagaInstanceId);
+
+    /// Makes the entity the owner of the saga instance. The saga instance is cancelled when the
+    /// entity is despawned.
+    fn set_instance_owner(&mut self, instance: SagaInstanceId, entity: Entity);
+
+    /// Stops all saga instances that are owned by the entity and drops their events.
+    fn cancel_owned_instances(&mut self, entity: Entity);
+}
+
+impl SagaControl for World {
+    fn disable_saga(&mut self, saga: SagaHandle) {
+        self.get_resource_or_init::<SagaStates>()
+            .disabled
+            .insert(saga.id());
+    }
+
+    fn enable_saga(&mut self, saga: SagaHandle) {
+        self.get_resource_or_init::<SagaStates>()
+            .disabled
+            .remove(&saga.id());
+    }
+
+    fn remove_saga(&mut self, saga: SagaHandle) {
+        let mut states = self.get_resource_or_init::<SagaStates>();
+        if !states.removed.insert(saga.id()) {
+            return;
+        }
+        let (removers, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut states.removers)
+            .into_iter()
+            .partition(|(removed, _)| *removed == saga.id());
+        states.removers = kept;
+        let (helpers, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut states.helpers)
+            .into_iter()
+            .partition(|(removed, _)| *removed == saga.id());
+        states.helpers = kept;
+        for (_, remover) in removers {
+            remover(self, saga.id());
+        }
+        for (_, unregister) in helpers {
+            unregister(self);
+        }
+        // The compensations of the saga's instances can't run anymore.
+        if let Some(mut compensations) = self.get_resource_mut::<Compensations>() {
+            compensations.forget_saga(saga.id());
+        }
+    }
+
+    fn cancel_instance(&mut self, instance: SagaInstanceId) {
+        cancel_instance(self, instance);
+    }
+
+    fn set_instance_owner(&mut self, instance: SagaInstanceId, entity: Entity) {
+        own_instance(instance, entity)(self);
+    }
+
+    fn cancel_owned_instances(&mut self, entity: Entity) {
+        let Some(instances) = self.get_resource::<SagaInstances>() else {
+            return;
+        };
+        let owned: Vec<_> = instances.owned_by(entity).collect();
+        for instance in owned {
+            cancel_instance(self, instance);
+        }
+    }
+}
+
+impl SagaControl for Commands<'_, '_> {
+    fn disable_saga(&mut self, saga: SagaHandle) {
+        self.queue(move |world: &mut World| world.disable_saga(saga));
+    }
+
+    fn enable_saga(&mut self, saga: SagaHandle) {
+        self.queue(move |world: &mut World| world.enable_saga(saga));
+    }
+
+    fn remove_saga(&mut self, saga: SagaHandle) {
+        self.queue(move |world: &mut World| world.remove_saga(saga));
+    }
+
+    fn cancel_instance(&mut self, instance: SagaInstanceId) {
+        self.queue(move |world: &mut World| world.cancel_instance(instance));
+    }
+
+    fn set_instance_owner(&mut self, instance: SagaInstanceId, entity: Entity) {
+        self.queue(move |world: &mut World| world.set_instance_owner(instance, entity));
+    }
+
+    fn cancel_owned_instances(&mut self, entity: Entity) {
+        self.queue(move |world: &mut World| world.cancel_owned_instances(entity));
+    }
+}
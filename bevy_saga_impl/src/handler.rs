@@ -200,7 +200,7 @@ where
         app: &mut App,
     ) -> ScheduleConfigs<ScheduleSystem>
     {
-        app.add_event_handler::<In, _>(self)
+        app.add_step_handler::<In, _>(self, None)
     }
 }
 
@@ -221,7 +221,7 @@ macro_rules! impl_event_handler {
             {
                 let ($($spf,)*) = self;
                 (
-                    $(app.add_event_handler::<In, _>($spf),)*
+                    $(app.add_step_handler::<In, _>($spf, None),)*
                 )
                     .into_configs()
             }
@@ -0,0 +1,592 @@
+use crate::SagaEvent;
+use crate::compensation::Compensations;
+use crate::handle::{SagaControl, SagaStates};
+use crate::lifecycle::{SagaCompleted, emit};
+#[cfg(feature = "testing")]
+use crate::testing::CapturedEvents;
+use bevy::ecs::error::BevyError;
+use bevy::ecs::component::HookContext;
+use bevy::ecs::system::{SystemId, SystemParam};
+use bevy::ecs::world::DeferredWorld;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::{Component, Entity, Event, EventWriter, Res, ResMut, Resource, World};
+use std::any::TypeId;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Identifies one run of a saga.
+///
+/// Every event that enters a saga from the outside starts a new saga instance. When several sagas
+/// handle the event, every saga gets its own instance. All events that are produced by the
+/// processors of that saga carry the same instance id. This includes the Err values
+/// of [result processors](crate::prelude::ErrStage) that are propagated through the Err saga.
+///
+/// Read the id of the running saga instance with the [SagaInstance] system parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SagaInstanceId {
+    id: u64,
+    saga: SagaId,
+}
+
+impl SagaInstanceId {
+    /// The saga this is an instance of.
+    pub fn saga(&self) -> SagaId {
+        self.saga
+    }
+}
+
+impl Display for SagaInstanceId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "saga#{}", self.id)
+    }
+}
+
+/// Identifies a saga that was added to the app with [add_saga](crate::SagaRegistry::add_saga).
+///
+/// Every call to `add_saga` adds a new saga, even if the same processors are passed again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SagaId(pub(crate) u64);
+
+impl Display for SagaId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "saga {}", self.0)
+    }
+}
+
+/// A system parameter that gives your processors and handlers access to the id of the saga
+/// instance they are running for.
+///
+/// When multiple events trigger the same saga in one update, every event starts its own saga
+/// instance. Use the instance id to tell the runs apart.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// use bevy_saga_impl::prelude::SagaInstance;
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct AttackTrigger;
+///
+/// #[saga_event]
+/// struct Damage;
+///
+/// fn attack(_: AttackTrigger, instance: SagaInstance) -> Damage {
+///     println!("Attacking in {}.", instance.id());
+///     Damage
+/// }
+///
+/// fn take_damage(_: Damage, instance: SagaInstance) {
+///     println!("Taking damage in {}.", instance.id());
+/// }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, (attack, take_damage));
+/// ```
+///
+/// The system parameter can only be used in systems that run as a step of a saga.
+#[derive(SystemParam)]
+pub struct SagaInstance<'w> {
+    current: Res<'w, CurrentSagaInstance>,
+}
+
+impl SagaInstance<'_> {
+    /// The id of the saga instance this step is running for.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the system is not running as a step of a saga.
+    pub fn id(&self) -> SagaInstanceId {
+        self.current
+            .instance
+            .expect("SagaInstance can only be used in systems that run as a step of a saga.")
+    }
+
+    /// The id the step is running under. In the branches of a [race](crate::prelude::race) or
+    /// [gather](crate::prelude::gather), this is the entry of the event, so the steps can keep the
+    /// events apart.
+    pub(crate) fn current(&self) -> SagaInstanceId {
+        self.current
+            .get()
+            .expect("SagaInstance can only be used in systems that run as a step of a saga.")
+    }
+}
+
+/// A resource used by bevy_saga to remember which saga instance is currently executing a step.
+///
+/// It's not recommended to use this resource in your own code.
+#[derive(Resource, Default)]
+pub struct CurrentSagaInstance {
+    current: Option<SagaInstanceId>,
+    /// The saga instance that `current` belongs to, if it is the entry of a branch.
+    instance: Option<SagaInstanceId>,
+}
+
+impl CurrentSagaInstance {
+    pub fn get(&self) -> Option<SagaInstanceId> {
+        self.current
+    }
+
+    /// The saga instance the step is running for, which the entries of branches belong to.
+    pub(crate) fn instance(&self) -> Option<SagaInstanceId> {
+        self.instance
+    }
+}
+
+/// A resource that holds on to saga instances until they are picked up by a following step.
+///
+/// A saga instance is finished once none of the registered stores hold it any longer.
+pub trait InstanceStore: Resource {
+    fn holds(&self, instance: SagaInstanceId) -> bool;
+
+    /// Drops the saga instance, because it was [cancelled](crate::prelude::SagaControl::cancel_instance).
+    fn cancel(&mut self, instance: SagaInstanceId);
+}
+
+type HoldsInstance = fn(&World, SagaInstanceId) -> bool;
+
+pub(crate) type CancelInstance = fn(&mut World, SagaInstanceId);
+
+/// A resource used by bevy_saga to hand out saga instance ids and to keep track of the stores that
+/// hold saga instances.
+///
+/// It's not recommended to use this resource in your own code.
+#[derive(Resource, Default)]
+pub struct SagaInstances {
+    next: u64,
+    running: HashSet<SagaInstanceId>,
+    stores: HashMap<TypeId, (HoldsInstance, CancelInstance)>,
+    cancelled: HashSet<SagaInstanceId>,
+    owners: HashMap<SagaInstanceId, Entity>,
+    /// The entries of branches, and the saga instances they belong to.
+    entries: HashMap<SagaInstanceId, SagaInstanceId>,
+    /// How often the steps of a saga instance are queued and haven't finished yet.
+    held: HashMap<SagaInstanceId, usize>,
+}
+
+impl SagaInstances {
+    pub fn start(&mut self, saga: SagaId) -> SagaInstanceId {
+        let instance = SagaInstanceId {
+            id: self.next,
+            saga,
+        };
+        self.next += 1;
+        self.running.insert(instance);
+        instance
+    }
+
+    /// Starts the entry of an event in a branch of a [race](crate::prelude::race) or
+    /// [gather](crate::prelude::gather), which runs the branch separately from the other events
+    /// of the saga instance. The saga instance is pending until the entry is finished.
+    pub(crate) fn enter(&mut self, instance: SagaInstanceId) -> SagaInstanceId {
+        let entry = self.start(instance.saga());
+        self.entries.insert(entry, instance);
+        entry
+    }
+
+    /// The saga instance that an entry of a branch belongs to, following nested branches. Saga
+    /// instances belong to themselves.
+    pub fn root(&self, instance: SagaInstanceId) -> SagaInstanceId {
+        match self.entries.get(&instance) {
+            Some(parent) => self.root(*parent),
+            None => instance,
+        }
+    }
+
+    /// The saga instance that the entry of a branch belongs to.
+    pub(crate) fn entered(&self, entry: SagaInstanceId) -> Option<SagaInstanceId> {
+        self.entries.get(&entry).copied()
+    }
+
+    /// Returns true if the saga instance was started and isn't finished yet.
+    pub(crate) fn is_running(&self, instance: SagaInstanceId) -> bool {
+        self.running.contains(&instance)
+    }
+
+    /// Keeps the saga instance pending while its steps are queued.
+    pub(crate) fn hold(&mut self, instance: SagaInstanceId) {
+        *self.held.entry(instance).or_default() += 1;
+    }
+
+    fn unhold(&mut self, instance: SagaInstanceId) {
+        if let Some(held) = self.held.get_mut(&instance) {
+            *held -= 1;
+            if *held == 0 {
+                self.held.remove(&instance);
+            }
+        }
+    }
+
+    pub fn register_store<S>(&mut self)
+    where
+        S: InstanceStore,
+    {
+        self.stores.insert(
+            TypeId::of::<S>(),
+            (
+                |world, instance| {
+                    world
+                        .get_resource::<S>()
+                        .is_some_and(|store| store.holds(instance))
+                },
+                |world, instance| {
+                    if let Some(mut store) = world.get_resource_mut::<S>() {
+                        store.cancel(instance);
+                    }
+                },
+            ),
+        );
+    }
+
+    /// The entity that owns the saga instance, if any.
+    pub fn owner(&self, instance: SagaInstanceId) -> Option<Entity> {
+        self.owners.get(&instance).copied()
+    }
+
+    /// Iterates over the saga instances that are owned by the entity.
+    pub fn owned_by(&self, entity: Entity) -> impl Iterator<Item = SagaInstanceId> {
+        self.owners
+            .iter()
+            .filter(move |(_, owner)| **owner == entity)
+            .map(|(instance, _)| *instance)
+    }
+
+    /// Returns true if the saga instance was cancelled while its current steps are running.
+    pub fn is_cancelled(&self, instance: SagaInstanceId) -> bool {
+        self.cancelled.contains(&instance)
+    }
+
+    /// Forgets the cancelled saga instances. Their steps have finished running and their events
+    /// were dropped from all stores.
+    pub(crate) fn clear_cancelled(&mut self) {
+        self.cancelled.clear();
+    }
+
+    pub(crate) fn is_pending(world: &World, instance: SagaInstanceId) -> bool {
+        let instances = world.resource::<SagaInstances>();
+        instances.held.contains_key(&instance)
+            || instances.entries.values().any(|parent| *parent == instance)
+            || instances
+                .stores
+                .values()
+                .any(|(holds, _)| holds(world, instance))
+    }
+}
+
+/// A resource used by bevy_saga to remember to which saga instance an event belongs.
+///
+/// It's not recommended to use this resource in your own code. It's exported from the crate for the
+/// `#[saga_router]` macro.
+#[derive(Resource)]
+pub struct EventInstances<R>
+where
+    R: Event,
+{
+    instances: HashMap<usize, SagaInstanceId>,
+    cancelled: HashSet<usize>,
+    _marker: PhantomData<R>,
+}
+
+impl<R> Default for EventInstances<R>
+where
+    R: Event,
+{
+    fn default() -> Self {
+        EventInstances {
+            instances: HashMap::default(),
+            cancelled: HashSet::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<R> EventInstances<R>
+where
+    R: Event,
+{
+    pub fn get(&self, event_id: usize) -> Option<SagaInstanceId> {
+        self.instances.get(&event_id).copied()
+    }
+
+    pub fn insert(&mut self, event_id: usize, instance: SagaInstanceId) {
+        self.instances.insert(event_id, instance);
+    }
+
+    pub fn remove(&mut self, event_id: usize) -> Option<SagaInstanceId> {
+        self.instances.remove(&event_id)
+    }
+
+    /// Returns true if the event belonged to a cancelled saga instance, and forgets about it.
+    pub(crate) fn take_cancelled(&mut self, event_id: usize) -> bool {
+        self.cancelled.remove(&event_id)
+    }
+}
+
+impl<R> InstanceStore for EventInstances<R>
+where
+    R: Event,
+{
+    fn holds(&self, instance: SagaInstanceId) -> bool {
+        self.instances.values().any(|held| *held == instance)
+    }
+
+    /// The events of the saga instance stay in the event queue, but they are skipped when they are
+    /// propagated.
+    fn cancel(&mut self, instance: SagaInstanceId) {
+        let cancelled: Vec<_> = self
+            .instances
+            .iter()
+            .filter(|(_, held)| **held == instance)
+            .map(|(event_id, _)| *event_id)
+            .collect();
+        for event_id in cancelled {
+            self.instances.remove(&event_id);
+            self.cancelled.insert(event_id);
+        }
+    }
+}
+
+/// A system parameter used by bevy_saga to send events that belong to the current saga instance.
+///
+/// It's not recommended to use this system parameter in your own code. It's exported from the
+/// crate for the `#[saga_router]` macro.
+///
+/// Events that no saga handles have no [EventInstances], so they don't belong to any saga instance.
+#[derive(SystemParam)]
+pub struct SagaWriter<'w, R>
+where
+    R: Event,
+{
+    writer: EventWriter<'w, R>,
+    instances: Option<ResMut<'w, EventInstances<R>>>,
+    current: Res<'w, CurrentSagaInstance>,
+    captured: EventCapture<'w>,
+}
+
+/// Collects the events that the steps write for the [SagaTestApp](crate::prelude::SagaTestApp).
+#[cfg(feature = "testing")]
+type EventCapture<'w> = Option<Res<'w, CapturedEvents>>;
+
+#[cfg(not(feature = "testing"))]
+type EventCapture<'w> = PhantomData<&'w ()>;
+
+impl<R> SagaWriter<'_, R>
+where
+    R: Event,
+{
+    pub fn write(&mut self, event: R) {
+        match self.current.get() {
+            Some(instance) => self.write_as(instance, event),
+            None => {
+                self.capture(&event);
+                self.writer.write(event);
+            }
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    fn capture(&self, event: &R) {
+        if let Some(captured) = &self.captured {
+            captured.capture_written(event);
+        }
+    }
+
+    #[cfg(not(feature = "testing"))]
+    fn capture(&self, _event: &R) {}
+
+    /// Sends an event on behalf of another saga instance than the current one.
+    pub fn write_as(&mut self, instance: SagaInstanceId, event: R) {
+        self.capture(&event);
+        let event_id = self.writer.write(event);
+        if let Some(instances) = &mut self.instances {
+            instances.insert(event_id.id, instance);
+        }
+    }
+}
+
+type InlineStep<R> = dyn Fn(&mut World, R) -> Result<(), BevyError> + Send + Sync;
+
+/// The system of a processor or handler of a saga.
+pub(crate) enum StepSystem<R>
+where
+    R: SagaEvent,
+{
+    /// A system that is registered in the world.
+    Registered(SystemId<R, ()>),
+    /// A plain function, for lightweight steps like
+    /// [map_event](crate::prelude::LightweightStage::map_event).
+    Inline(Arc<InlineStep<R>>),
+}
+
+impl<R> Clone for StepSystem<R>
+where
+    R: SagaEvent,
+{
+    fn clone(&self) -> Self {
+        match self {
+            StepSystem::Registered(id) => StepSystem::Registered(*id),
+            StepSystem::Inline(step) => StepSystem::Inline(step.clone()),
+        }
+    }
+}
+
+/// Runs one step of a saga on behalf of a saga instance.
+pub(crate) fn run_saga_step<R>(
+    system: StepSystem<R>,
+    instance: SagaInstanceId,
+    event: R,
+) -> impl FnOnce(&mut World) -> Result<(), BevyError>
+where
+    R: SagaEvent,
+{
+    move |world| {
+        run_as_instance(world, instance, |world| match system {
+            StepSystem::Registered(id) => Ok(world.run_system_with(id, event)?),
+            StepSystem::Inline(step) => step(world, event),
+        })
+    }
+}
+
+/// Runs `f` while `instance` is the current saga instance.
+pub(crate) fn run_as_instance<T>(
+    world: &mut World,
+    instance: SagaInstanceId,
+    f: impl FnOnce(&mut World) -> T,
+) -> T {
+    let root = world
+        .get_resource::<SagaInstances>()
+        .map_or(instance, |instances| instances.root(instance));
+    let mut current = world.resource_mut::<CurrentSagaInstance>();
+    let previous = (current.current.replace(instance), current.instance.replace(root));
+    let result = f(world);
+    let mut current = world.resource_mut::<CurrentSagaInstance>();
+    (current.current, current.instance) = previous;
+    result
+}
+
+/// Releases a saga instance after all steps have processed its event. If no other events or steps
+/// hold on to the instance, it is finished.
+pub(crate) fn release_instance(instance: SagaInstanceId) -> impl FnOnce(&mut World) {
+    move |world| {
+        world.resource_mut::<SagaInstances>().unhold(instance);
+        complete_instance(world, instance);
+    }
+}
+
+/// Finishes the saga instance if nothing holds on to it any longer. A finished entry of a
+/// branch may complete the saga instance it belongs to.
+fn complete_instance(world: &mut World, instance: SagaInstanceId) {
+    if SagaInstances::is_pending(world, instance) {
+        return;
+    }
+    let parent = world.resource::<SagaInstances>().entries.get(&instance).copied();
+    if !finish_instance(world, instance) {
+        return;
+    }
+    match parent {
+        Some(parent) => complete_instance(world, parent),
+        None => {
+            let name = world.resource::<SagaStates>().name(instance.saga());
+            emit(SagaCompleted { name, instance })(world);
+        }
+    }
+}
+
+/// A component that bevy_saga adds to the entities that own saga instances. When the entity is
+/// despawned, its saga instances are cancelled.
+#[derive(Component)]
+#[component(on_despawn = cancel_owned_instances)]
+pub struct SagaOwner;
+
+fn cancel_owned_instances(mut world: DeferredWorld, context: HookContext) {
+    world.commands().cancel_owned_instances(context.entity);
+}
+
+/// Binds a saga instance to the entity that owns it. If the entity doesn't exist anymore, the
+/// saga instance is cancelled right away.
+pub(crate) fn own_instance(instance: SagaInstanceId, entity: Entity) -> impl FnOnce(&mut World) {
+    move |world| {
+        let instance = world
+            .get_resource::<SagaInstances>()
+            .map_or(instance, |instances| instances.root(instance));
+        let Ok(mut owner) = world.get_entity_mut(entity) else {
+            cancel_instance(world, instance);
+            return;
+        };
+        owner.insert(SagaOwner);
+        world
+            .get_resource_or_init::<SagaInstances>()
+            .owners
+            .insert(instance, entity);
+    }
+}
+
+/// Cancels a saga instance. It's dropped from all stores and its remaining steps don't run.
+/// Instances that are not running anymore are left alone.
+pub(crate) fn cancel_instance(world: &mut World, instance: SagaInstanceId) {
+    let Some(instances) = world.get_resource::<SagaInstances>() else {
+        return;
+    };
+    let instance = instances.root(instance);
+    if drop_instance(world, instance) {
+        world.send_event(SagaCancelled { instance });
+    }
+}
+
+/// Drops the entry of a [race](crate::prelude::race) or [gather](crate::prelude::gather) branch
+/// from all stores, without cancelling the saga instance it belongs to. The saga instance
+/// completes if nothing else holds on to it.
+pub(crate) fn abandon_entry(world: &mut World, entry: SagaInstanceId) {
+    let parent = world.resource::<SagaInstances>().entered(entry);
+    if !drop_instance(world, entry) {
+        return;
+    }
+    if let Some(parent) = parent {
+        complete_instance(world, parent);
+    }
+}
+
+/// Drops the saga instance and the entries of its branches from all stores. Returns false if it
+/// wasn't running.
+fn drop_instance(world: &mut World, instance: SagaInstanceId) -> bool {
+    let mut instances = world.resource_mut::<SagaInstances>();
+    if !instances.running.contains(&instance) || !instances.cancelled.insert(instance) {
+        return false;
+    }
+    let entries: Vec<_> = instances
+        .entries
+        .iter()
+        .filter(|(_, parent)| **parent == instance)
+        .map(|(entry, _)| *entry)
+        .collect();
+    let cancels: Vec<_> = instances.stores.values().map(|(_, cancel)| *cancel).collect();
+    for entry in entries {
+        drop_instance(world, entry);
+    }
+    for cancel in cancels {
+        cancel(world, instance);
+    }
+    finish_instance(world, instance);
+    true
+}
+
+/// Forgets everything about the saga instance. Returns false if it was already finished.
+fn finish_instance(world: &mut World, instance: SagaInstanceId) -> bool {
+    if let Some(mut compensations) = world.get_resource_mut::<Compensations>() {
+        compensations.forget(instance);
+    }
+    let Some(mut instances) = world.get_resource_mut::<SagaInstances>() else {
+        return false;
+    };
+    instances.owners.remove(&instance);
+    instances.entries.remove(&instance);
+    instances.held.remove(&instance);
+    instances.running.remove(&instance)
+}
+
+/// An event that is sent when a saga instance is
+/// [cancelled](crate::prelude::SagaControl::cancel_instance).
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SagaCancelled {
+    pub instance: SagaInstanceId,
+}
@@ -1,3 +1,4 @@
+use crate::graph::SagaEdgeLabel;
 use crate::processor::EventProcessor;
 use crate::{SagaEvent, extension::BevySagaUtil};
 use bevy::ecs::schedule::ScheduleConfigs;
@@ -5,42 +6,44 @@ use bevy::ecs::system::ScheduleSystem;
 use bevy::prelude::{App, Event, IntoScheduleConfigs, SystemParamFunction};
 use variadics_please::all_tuples;
 
-pub struct OptionProcessor<T>(T);
+pub struct IterProcessor<T>(T);
 
-impl<SPF, M, In, Out> EventProcessor<OptionProcessor<(M,)>> for SPF
+impl<SPF, M, In, I> EventProcessor<IterProcessor<(M,)>> for SPF
 where
     In: SagaEvent,
-    Out: Event,
-    SPF: SystemParamFunction<M, In = In, Out = Option<Out>>,
+    I: IntoIterator + 'static,
+    I::Item: Event,
+    SPF: SystemParamFunction<M, In = In, Out = I>,
     M: 'static,
 {
     type In = In;
-    type Out = Out;
+    type Out = I::Item;
 
     fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
-        app.add_option_processor::<In, Out, _>(self)
+        app.add_iter_processor::<In, I, _>(self)
     }
 }
 
-macro_rules! impl_option_processor {
+macro_rules! impl_iter_processor {
     ($(#[$meta:meta])* $(($SPF:ident, $p:ident, $M:ident)),*) => {
-        impl<PROC, MPROC, $($SPF,)* $($M,)* In, Out> EventProcessor<OptionProcessor<(MPROC, $($M,)*)>> for (PROC, $($SPF,)*)
+        impl<PROC, MPROC, $($SPF,)* $($M,)* In, I> EventProcessor<IterProcessor<(MPROC, $($M,)*)>> for (PROC, $($SPF,)*)
         where
             In: SagaEvent,
-            Out: Event,
-            PROC: SystemParamFunction<MPROC, In = In, Out = Option<Out>>,
+            I: IntoIterator + 'static,
+            I::Item: Event,
+            PROC: SystemParamFunction<MPROC, In = In, Out = I>,
             $($SPF: SystemParamFunction<$M, In = In, Out = ()>,)*
             MPROC: 'static,
             $($M: 'static,)*
         {
             type In = In;
-            type Out = Out;
+            type Out = I::Item;
 
             fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
                 let (proc, $($p,)*) = self;
                 (
-                    app.add_option_processor::<In, Out, _>(proc),
-                    $(app.add_event_handler::<In, _>($p),)*
+                    app.add_iter_processor::<In, I, _>(proc),
+                    $(app.add_step_handler::<In, _>($p, Some(SagaEdgeLabel::Sibling)),)*
                 )
                     .into_configs()
             }
@@ -48,4 +51,4 @@ macro_rules! impl_option_processor {
     }
 }
 
-all_tuples!(impl_option_processor, 1, 15, SPF, p, M);
\ No newline at end of file
+all_tuples!(impl_iter_processor, 1, 15, SPF, p, M);
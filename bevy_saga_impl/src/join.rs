@@ -0,0 +1,410 @@
+use crate::await_event::Correlated;
+use crate::graph::{SagaGraph, SagaStepId};
+use crate::instance::{
+    InstanceStore, SagaId, SagaInstance, SagaInstanceId, SagaInstances, SagaWriter,
+};
+use crate::processor::EventProcessor;
+use crate::timeout::{SuspendedInstances, SuspendingStep};
+use crate::extension::{SagaRegistrations, add_saga_consumer};
+use crate::{SagaEvent, extension::BevySagaUtil};
+use bevy::app::App;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Event, IntoScheduleConfigs, Res, ResMut, Resource, SystemInput};
+use bevy::time::{Time, Virtual};
+use std::any::{Any, type_name};
+use std::collections::VecDeque;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::time::Duration;
+use variadics_please::all_tuples_enumerated;
+
+/// An event that arrived at a join.
+struct Arrival {
+    /// Only the first event of the tuple belongs to a saga instance.
+    instance: Option<SagaInstanceId>,
+    /// When the event arrived, in [virtual time](Virtual).
+    at: Duration,
+    event: Box<dyn Any + Send + Sync>,
+}
+
+/// Tuples of saga events that can be [joined](join).
+///
+/// This trait is implemented for tuples of 2 to 15 saga events that are [correlated](Correlated)
+/// by the same key type. You don't have to implement it yourself.
+pub trait JoinEvents: Clone + Send + Sync + 'static {
+    /// The correlation key all events of the tuple share.
+    type Key: Eq + Hash + Clone + Send + Sync + 'static;
+    /// The first event of the tuple. The joined saga instance continues as the instance of this
+    /// event.
+    type First: SagaEvent + Correlated<Key = Self::Key>;
+
+    /// Adds one event handler per event of the tuple that waits for the other events.
+    fn register_arrivals(app: &mut App, step: SagaStepId) -> ScheduleConfigs<ScheduleSystem>;
+
+    /// Builds the tuple from one event per position.
+    fn assemble(events: Vec<Box<dyn Any + Send + Sync>>) -> Self;
+}
+
+/// The event that is propagated through a saga once all events of a [join] have arrived.
+///
+/// It holds one event per type of the tuple, in the same order.
+#[derive(Clone)]
+pub struct Joined<T>(pub T);
+
+impl<T> Event for Joined<T>
+where
+    T: JoinEvents,
+{
+    type Traversal = ();
+}
+
+impl<T> SystemInput for Joined<T>
+where
+    T: JoinEvents,
+{
+    type Param<'i> = Joined<T>;
+    type Inner<'i> = Joined<T>;
+
+    fn wrap(this: Self::Inner<'_>) -> Self::Param<'_> {
+        this
+    }
+}
+
+impl<T> SagaEvent for Joined<T> where T: JoinEvents {}
+
+/// A resource that holds the events of a [join] that are waiting for the other events of the
+/// tuple.
+///
+/// The events are grouped by saga and by their correlation key.
+#[derive(Resource)]
+pub struct PendingJoins<T>
+where
+    T: JoinEvents,
+{
+    pending: HashMap<(SagaId, T::Key), Vec<VecDeque<Arrival>>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for PendingJoins<T>
+where
+    T: JoinEvents,
+{
+    fn default() -> Self {
+        PendingJoins {
+            pending: HashMap::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> PendingJoins<T>
+where
+    T: JoinEvents,
+{
+    /// Iterates over all saga instances that wait with an event for the rest of the tuple.
+    pub fn iter(&self) -> impl Iterator<Item = SagaInstanceId> {
+        self.arrivals().filter_map(|arrival| arrival.instance)
+    }
+
+    /// Returns true if any event with this correlation key is waiting.
+    pub fn contains_key(&self, key: &T::Key) -> bool {
+        self.pending.keys().any(|(_, pending)| pending == key)
+    }
+
+    /// The number of waiting events.
+    pub fn len(&self) -> usize {
+        self.arrivals().count()
+    }
+
+    /// Returns true if no event is waiting.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    fn arrivals(&self) -> impl Iterator<Item = &Arrival> {
+        self.pending.values().flatten().flatten()
+    }
+
+    /// Stores an event at its position of the tuple. Returns one event per position once all of
+    /// them have arrived.
+    fn arrive(
+        &mut self,
+        key: (SagaId, T::Key),
+        position: usize,
+        arrival: Arrival,
+        len: usize,
+    ) -> Option<Vec<Arrival>> {
+        let slots = self
+            .pending
+            .entry(key.clone())
+            .or_insert_with(|| (0..len).map(|_| VecDeque::new()).collect());
+        slots[position].push_back(arrival);
+        if slots.iter().any(VecDeque::is_empty) {
+            return None;
+        }
+        let complete = slots
+            .iter_mut()
+            .filter_map(VecDeque::pop_front)
+            .collect();
+        if slots.iter().all(VecDeque::is_empty) {
+            self.pending.remove(&key);
+        }
+        Some(complete)
+    }
+}
+
+impl<T> InstanceStore for PendingJoins<T>
+where
+    T: JoinEvents,
+{
+    fn holds(&self, instance: SagaInstanceId) -> bool {
+        self.iter().any(|held| held == instance)
+    }
+
+    fn cancel(&mut self, instance: SagaInstanceId) {
+        self.abandon(instance);
+    }
+}
+
+impl<T> SuspendedInstances for PendingJoins<T>
+where
+    T: JoinEvents,
+{
+    fn abandon(&mut self, instance: SagaInstanceId) {
+        self.pending.retain(|_, slots| {
+            for slot in slots.iter_mut() {
+                slot.retain(|arrival| arrival.instance != Some(instance));
+            }
+            slots.iter().any(|slot| !slot.is_empty())
+        });
+    }
+
+    /// The saga instance waits since the first of the events it will be joined with arrived.
+    fn waiting_since(&self, instance: SagaInstanceId) -> Option<Duration> {
+        self.pending.values().find_map(|slots| {
+            let place = slots[0]
+                .iter()
+                .position(|arrival| arrival.instance == Some(instance))?;
+            slots
+                .iter()
+                .filter_map(|slot| slot.get(place))
+                .map(|arrival| arrival.at)
+                .min()
+        })
+    }
+
+    /// The other events of the tuple expire once they waited as long as the timeout.
+    fn expire(&mut self, saga: SagaId, since: Duration) {
+        self.pending.retain(|(joined, _), slots| {
+            if *joined == saga {
+                for slot in slots.iter_mut() {
+                    slot.retain(|arrival| arrival.instance.is_some() || arrival.at > since);
+                }
+            }
+            slots.iter().any(|slot| !slot.is_empty())
+        });
+    }
+}
+
+/// Adds the event handler for the event at `position` of the tuple.
+fn add_arrival<T, E>(
+    app: &mut App,
+    step: SagaStepId,
+    position: usize,
+    len: usize,
+) -> ScheduleConfigs<ScheduleSystem>
+where
+    T: JoinEvents,
+    E: SagaEvent + Correlated<Key = T::Key>,
+{
+    // The step was recorded with the first event of the tuple as its input.
+    if position > 0 {
+        app.world_mut()
+            .resource_mut::<SagaGraph>()
+            .add_input(type_name::<E>(), step, None);
+        app.world_mut()
+            .resource_mut::<SagaRegistrations>()
+            .order::<E, Joined<T>>();
+    }
+    let saga = app.world_mut().resource_mut::<SagaRegistrations>().current();
+    if position == 0 {
+        return app.add_event_handler(
+            move |event: E,
+                  instance: SagaInstance,
+                  time: Res<Time<Virtual>>,
+                  mut pending: ResMut<PendingJoins<T>>,
+                  mut writer: SagaWriter<Joined<T>>| {
+                let key = (saga, event.correlation_key());
+                let arrival = Arrival {
+                    instance: Some(instance.current()),
+                    at: time.elapsed(),
+                    event: Box::new(event),
+                };
+                if let Some(complete) = pending.arrive(key, position, arrival, len) {
+                    send_joined(complete, &mut writer);
+                }
+            },
+        );
+    }
+    // The other events of the tuple only complete the joins of the first events, so they don't
+    // start saga instances of their own.
+    add_saga_consumer(
+        app,
+        move |event: E,
+              time: Res<Time<Virtual>>,
+              mut pending: ResMut<PendingJoins<T>>,
+              mut writer: SagaWriter<Joined<T>>| {
+            let key = (saga, event.correlation_key());
+            let arrival = Arrival {
+                instance: None,
+                at: time.elapsed(),
+                event: Box::new(event),
+            };
+            if let Some(complete) = pending.arrive(key, position, arrival, len) {
+                send_joined(complete, &mut writer);
+            }
+        },
+    )
+}
+
+/// Continues the saga instance of the first event of a complete tuple with the joined events.
+fn send_joined<T>(complete: Vec<Arrival>, writer: &mut SagaWriter<Joined<T>>)
+where
+    T: JoinEvents,
+{
+    let instance = complete[0].instance;
+    let events = complete.into_iter().map(|arrival| arrival.event).collect();
+    if let Some(instance) = instance {
+        writer.write_as(instance, Joined(T::assemble(events)));
+    }
+}
+
+macro_rules! impl_join_events {
+    (($n0:tt, $A0:ident) $(, ($n:tt, $A:ident))*) => {
+        impl<$A0, $($A,)*> JoinEvents for ($A0, $($A,)*)
+        where
+            $A0: SagaEvent + Correlated,
+            $($A: SagaEvent + Correlated<Key = <$A0 as Correlated>::Key>,)*
+        {
+            type Key = <$A0 as Correlated>::Key;
+            type First = $A0;
+
+            fn register_arrivals(app: &mut App, step: SagaStepId) -> ScheduleConfigs<ScheduleSystem> {
+                let len = [$n0, $($n,)*].len();
+                (
+                    add_arrival::<Self, $A0>(app, step, $n0, len),
+                    $(add_arrival::<Self, $A>(app, step, $n, len),)*
+                )
+                    .into_configs()
+            }
+
+            fn assemble(events: Vec<Box<dyn Any + Send + Sync>>) -> Self {
+                let mut events = events.into_iter();
+                let mut next = || events.next().expect("One event per position of the tuple.");
+                (
+                    *next().downcast::<$A0>().expect("The event has the type of its position."),
+                    $(*next().downcast::<$A>().expect("The event has the type of its position."),)*
+                )
+            }
+        }
+    };
+}
+
+all_tuples_enumerated!(impl_join_events, 2, 15, A);
+
+pub struct Join<T>(PhantomData<fn() -> T>);
+
+pub struct JoinM;
+
+impl<T> EventProcessor<JoinM> for Join<T>
+where
+    T: JoinEvents,
+{
+    type In = T::First;
+    type Out = Joined<T>;
+
+    fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        app.init_resource::<PendingJoins<T>>();
+        app.init_resource::<Time<Virtual>>();
+        let name = format!("join<{}>", type_name::<T>());
+        let step = app.record_saga_step::<T::First>(&name, None);
+        app.record_saga_flow::<T::First, Joined<T>>(step, None);
+        let arrivals = T::register_arrivals(app, step);
+        app.world_mut()
+            .resource_mut::<SagaInstances>()
+            .register_store::<PendingJoins<T>>();
+        arrivals
+    }
+}
+
+impl<T> SuspendingStep<JoinM> for Join<T>
+where
+    T: JoinEvents,
+{
+    type Suspended = PendingJoins<T>;
+}
+
+/// A saga step that waits until one event of every type of the tuple `T` has arrived with the
+/// same [correlation key](Correlated), and then propagates all of them together in a [Joined]
+/// event.
+///
+/// The events may come from this saga, for example from the processor before the join, from other
+/// sagas, or from outside of any saga. They may arrive in any order, in the same update or spread
+/// over several updates. Until the tuple is complete, the events that already arrived wait in the
+/// [PendingJoins] resource. When several events of the same type arrive for a key, they are joined
+/// in the order they arrived.
+///
+/// The joined saga instance continues as the instance of the first event of the tuple. The other
+/// events of the tuple don't start saga instances of their own, and the saga instances they were
+/// produced in finish.
+///
+/// Add a [timeout](crate::prelude::TimeoutStage) to the step to stop waiting after a while. The
+/// deadline starts when the earliest of the events that would be joined arrived, whatever its
+/// position in the tuple. Only the saga instance of the first event times out. The other events
+/// are dropped once they waited as long as the timeout, even when no first event of the tuple
+/// arrived for their key. Without a timeout, all events wait until their tuple is complete.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::Entity;
+/// use bevy_saga_impl::prelude::{join, Correlated, Joined};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct PathFound(Entity);
+///
+/// #[saga_event]
+/// struct TargetLocked(Entity);
+///
+/// #[saga_event]
+/// struct Engage(Entity);
+///
+/// impl Correlated for PathFound {
+///     type Key = Entity;
+///
+///     fn correlation_key(&self) -> Entity { self.0 }
+/// }
+///
+/// impl Correlated for TargetLocked {
+///     type Key = Entity;
+///
+///     fn correlation_key(&self) -> Entity { self.0 }
+/// }
+///
+/// fn engage(Joined((path, target)): Joined<(PathFound, TargetLocked)>) -> Engage {
+///     Engage(path.0)
+/// }
+///
+/// fn attack(_: Engage, /* other queries or resources */) { }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, (join::<(PathFound, TargetLocked)>(), engage, attack));
+/// ```
+pub fn join<T>() -> Join<T>
+where
+    T: JoinEvents,
+{
+    Join(PhantomData)
+}
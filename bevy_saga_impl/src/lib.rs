@@ -1,16 +1,44 @@
-use bevy::prelude::{Event, SystemInput};
+use bevy::prelude::{Entity, Event, SystemInput};
 
+mod async_processor;
+mod await_event;
+mod branches;
+mod compensation;
+mod condition;
+mod config;
+mod diagnostics;
 mod extension;
+mod gather;
+mod graph;
+mod handle;
 mod handler;
-mod option_processor;
+mod instance;
+mod iter_processor;
+mod join;
+mod lifecycle;
+mod lightweight;
 pub mod prelude;
 mod processor;
+mod race;
+#[cfg(feature = "serde")]
+mod record;
 mod result_handler;
 mod result_processor;
+mod retry;
 mod saga;
+#[cfg(feature = "testing")]
+mod testing;
+mod timeout;
+mod trace;
 mod util;
 
 pub use extension::SagaRegistry;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde_json;
 
 /// The trait type that propagates through your sagas.
 ///
@@ -21,4 +49,50 @@ pub use extension::SagaRegistry;
 ///
 /// The attribute `#[saga_router]` indirectly also implements SagaEvent so you don't have to add
 /// the `#[saga_event]` attribute if your type is already attributed with `#[saga_router]`.
-pub trait SagaEvent: Event + Clone + SystemInput<Inner<'static> = Self> {}
+///
+/// Mark a field of type `Entity` or `Option<Entity>` with `#[owner]` to bind the saga instances
+/// the event starts to that entity. When the entity is despawned, its saga instances are
+/// [cancelled](crate::prelude::SagaControl::cancel_owned_instances).
+///
+/// With the `serde` feature, write `#[saga_event(serde)]` to derive serde's `Serialize` and
+/// `Deserialize` for the event, so it can be recorded and replayed by the `SagaRecorderPlugin` and
+/// the `SagaReplayPlugin`.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::{Entity, Query};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct AttackTrigger {
+///     #[owner]
+///     by: Entity,
+/// }
+///
+/// // Despawning the attacker cancels the attacks it started.
+/// fn attack(trigger: AttackTrigger, /* other queries or resources */) { }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, attack);
+/// ```
+pub trait SagaEvent: Event + Clone + for<'i> SystemInput<Param<'i> = Self, Inner<'i> = Self> {
+    /// The entity that owns the saga instances this event starts.
+    fn owner(&self) -> Option<Entity> {
+        None
+    }
+
+    /// Serializes the event for a [SagaRecording](crate::prelude::SagaRecording), if it opted into
+    /// serde with `#[saga_event(serde)]`.
+    #[cfg(feature = "serde")]
+    #[doc(hidden)]
+    fn to_record(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Deserializes a recorded event, if it opted into serde with `#[saga_event(serde)]`.
+    #[cfg(feature = "serde")]
+    #[doc(hidden)]
+    fn from_record(_value: serde_json::Value) -> Option<serde_json::Result<Self>> {
+        None
+    }
+}
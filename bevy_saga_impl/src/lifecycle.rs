@@ -0,0 +1,97 @@
+use crate::instance::SagaInstanceId;
+use bevy::prelude::{Event, World};
+use std::sync::Arc;
+
+/// An event that is sent when an event starts a new saga instance.
+///
+/// The lifecycle events [SagaStarted], [SagaStepCompleted], [SagaCompleted] and [SagaFailed] are
+/// sent as ordinary events, so they can be read with an [EventReader](bevy::prelude::EventReader).
+/// They are also triggered, so [observers](bevy::prelude::Observer) can react to them right away.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::Trigger;
+/// use bevy_saga_impl::prelude::{ErrStage, OkStage, SagaCompleted, SagaFailed, SagaStarted};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Attack;
+///
+/// #[saga_event]
+/// struct Damage;
+///
+/// #[saga_event]
+/// struct Missed;
+///
+/// fn attack(_: Attack) -> Result<Damage, Missed> { Err(Missed) }
+/// fn take_damage(_: Damage) { }
+/// fn miss(_: Missed) { }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, attack.ok(take_damage).err(miss));
+/// app.add_observer(|started: Trigger<SagaStarted>| {
+///     println!("{} started {} with {}.", started.name, started.instance, started.event);
+/// });
+/// app.add_observer(|failed: Trigger<SagaFailed>| {
+///     println!("{} failed with {}.", failed.instance, failed.error);
+/// });
+/// app.add_observer(|completed: Trigger<SagaCompleted>| {
+///     println!("{} completed.", completed.instance);
+/// });
+/// ```
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct SagaStarted {
+    /// The name of the saga.
+    pub name: Arc<str>,
+    pub instance: SagaInstanceId,
+    /// The type name of the event that started the saga instance.
+    pub event: &'static str,
+}
+
+/// An event that is sent when a processor or handler of a saga ran for a saga instance.
+///
+/// Steps that suspend the saga instance, like [await_event](crate::prelude::await_event), complete
+/// when they suspend it.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct SagaStepCompleted {
+    /// The name of the saga.
+    pub name: Arc<str>,
+    pub instance: SagaInstanceId,
+    /// The name of the step, as it appears in the [SagaGraph](crate::prelude::SagaGraph).
+    pub step: Arc<str>,
+    /// The type name of the event the step received.
+    pub event: &'static str,
+}
+
+/// An event that is sent when a saga instance finishes, because none of its events are propagated
+/// or held any longer.
+///
+/// Instances that [failed](SagaFailed) complete as well, after their Err value was propagated.
+/// [Cancelled](crate::prelude::SagaCancelled) instances don't complete.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct SagaCompleted {
+    /// The name of the saga.
+    pub name: Arc<str>,
+    pub instance: SagaInstanceId,
+}
+
+/// An event that is sent when a [result processor](crate::prelude::OkStage) of a saga returns Err.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct SagaFailed {
+    /// The name of the saga.
+    pub name: Arc<str>,
+    pub instance: SagaInstanceId,
+    /// The type name of the Err value.
+    pub error: &'static str,
+}
+
+/// Sends the lifecycle event and triggers it for observers.
+pub(crate) fn emit<E>(event: E) -> impl FnOnce(&mut World)
+where
+    E: Event + Clone,
+{
+    move |world| {
+        world.send_event(event.clone());
+        world.trigger(event);
+    }
+}
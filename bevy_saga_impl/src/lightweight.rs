@@ -0,0 +1,154 @@
+use crate::processor::EventProcessor;
+use crate::{SagaEvent, extension::BevySagaUtil};
+use bevy::app::App;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::IntoScheduleConfigs;
+
+struct Then<Processor, F> {
+    processor: Processor,
+    name: &'static str,
+    then: F,
+}
+
+pub struct ThenM<T>(T);
+
+impl<Processor, F, MP, Rs> EventProcessor<ThenM<(MP, Rs)>> for Then<Processor, F>
+where
+    Processor: EventProcessor<MP>,
+    Processor::Out: SagaEvent,
+    F: Fn(Processor::Out) -> Option<Rs> + Send + Sync + 'static,
+    Rs: SagaEvent,
+{
+    type In = Processor::In;
+    type Out = Rs;
+
+    fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        let Then {
+            processor,
+            name,
+            then,
+        } = self;
+        (
+            processor.register_processor(app),
+            app.add_inline_step(name, then),
+        )
+            .chain()
+    }
+}
+
+/// This trait provides the `map_event`, `filter_event` and `inspect_event` methods on event
+/// processors.
+///
+/// These lightweight steps take a closure instead of a system. They can't access queries or
+/// resources, but they are cheap: the closure is called right away when the output event of the
+/// processor is propagated, without registering and running a one-shot system. Its output is
+/// handed directly to the following steps of the saga. It isn't sent as an event, so
+/// [EventReaders](bevy::prelude::EventReader) and other sagas don't see it.
+///
+/// - `map_event` converts the output event of the processor into another event.
+/// - `filter_event` drops the output events for which the predicate returns false. The saga instance
+///   finishes without running the remaining steps.
+/// - `inspect_event` looks at the output events and passes them on unchanged.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// use bevy_saga_impl::prelude::LightweightStage;
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Attack(u8);
+///
+/// #[saga_event]
+/// struct Damage(u8);
+///
+/// #[saga_event]
+/// struct Hit(u8);
+///
+/// fn attack(Attack(strength): Attack) -> Damage { Damage(strength) }
+/// fn take_hit(_: Hit, /* other queries or resources */) { }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, (
+///     attack
+///         .filter_event(|Damage(damage)| *damage > 0)
+///         .inspect_event(|Damage(damage)| println!("Dealing {damage} damage."))
+///         .map_event(|Damage(damage)| Hit(damage)),
+///     take_hit,
+/// ));
+/// ```
+///
+/// The methods end in `_event`, so they don't clash with the `map` method of Bevy's
+/// [IntoSystem](bevy::prelude::IntoSystem), which systems implement as well.
+pub trait LightweightStage<MP>: EventProcessor<MP> + Sized
+where
+    Self::Out: SagaEvent,
+{
+    fn map_event<Rs, F>(self, f: F) -> impl EventProcessor<ThenM<(MP, Rs)>, In = Self::In, Out = Rs>
+    where
+        F: Fn(Self::Out) -> Rs + Send + Sync + 'static,
+        Rs: SagaEvent;
+
+    fn filter_event<F>(
+        self,
+        predicate: F,
+    ) -> impl EventProcessor<ThenM<(MP, Self::Out)>, In = Self::In, Out = Self::Out>
+    where
+        F: Fn(&Self::Out) -> bool + Send + Sync + 'static;
+
+    fn inspect_event<F>(
+        self,
+        f: F,
+    ) -> impl EventProcessor<ThenM<(MP, Self::Out)>, In = Self::In, Out = Self::Out>
+    where
+        F: Fn(&Self::Out) + Send + Sync + 'static;
+}
+
+impl<Processor, MP> LightweightStage<MP> for Processor
+where
+    Processor: EventProcessor<MP>,
+    Processor::Out: SagaEvent,
+{
+    fn map_event<Rs, F>(self, f: F) -> impl EventProcessor<ThenM<(MP, Rs)>, In = Self::In, Out = Rs>
+    where
+        F: Fn(Self::Out) -> Rs + Send + Sync + 'static,
+        Rs: SagaEvent,
+    {
+        Then {
+            processor: self,
+            name: "map_event",
+            then: move |event| Some(f(event)),
+        }
+    }
+
+    fn filter_event<F>(
+        self,
+        predicate: F,
+    ) -> impl EventProcessor<ThenM<(MP, Self::Out)>, In = Self::In, Out = Self::Out>
+    where
+        F: Fn(&Self::Out) -> bool + Send + Sync + 'static,
+    {
+        Then {
+            processor: self,
+            name: "filter_event",
+            then: move |event| predicate(&event).then_some(event),
+        }
+    }
+
+    fn inspect_event<F>(
+        self,
+        f: F,
+    ) -> impl EventProcessor<ThenM<(MP, Self::Out)>, In = Self::In, Out = Self::Out>
+    where
+        F: Fn(&Self::Out) + Send + Sync + 'static,
+    {
+        Then {
+            processor: self,
+            name: "inspect_event",
+            then: move |event| {
+                f(&event);
+                Some(event)
+            },
+        }
+    }
+}
@@ -1,6 +1,33 @@
+pub use crate::async_processor::PendingTasks;
+pub use crate::await_event::{await_event, Awaited, Correlated, PendingAwaits};
+pub use crate::compensation::CompensateStage;
+pub use crate::condition::{ConditionPolicy, Conditioned, HeldEvents, RunIfStage};
+pub use crate::config::{SagaConfig, SagaErrorHandler};
+pub use crate::diagnostics::{SagaDiagnostics, SagaDiagnosticsPlugin, SagaStepMetric};
+pub use crate::gather::{gather, Gathered};
+pub use crate::graph::{SagaEdge, SagaEdgeLabel, SagaGraph, SagaNode, SagaStep, SagaStepId};
+pub use crate::handle::{SagaControl, SagaHandle, SagaStates};
 pub use crate::handler::EventHandler;
-pub use crate::extension::BevySagaUtil;
+pub use crate::extension::{BevySagaUtil, SagaRegistrations};
+pub use crate::instance::{
+    EventInstances, SagaCancelled, SagaId, SagaInstance, SagaInstanceId, SagaInstances, SagaOwner,
+    SagaWriter,
+};
+pub use crate::join::{join, JoinEvents, Joined, PendingJoins};
+pub use crate::lifecycle::{SagaCompleted, SagaFailed, SagaStarted, SagaStepCompleted};
+pub use crate::lightweight::LightweightStage;
 pub use crate::processor::EventProcessor;
+pub use crate::race::race;
+#[cfg(feature = "serde")]
+pub use crate::record::{
+    RecordedEvent, SagaRecorderPlugin, SagaRecording, SagaReplay, SagaReplayPlugin, SagaReplayers,
+};
 pub use crate::result_handler::{ErrStage, OkStage};
+pub use crate::retry::{PendingRetries, RetryPolicy, RetryStage};
 pub use crate::saga::Saga;
-pub use crate::util::{process_event, EventProcessors};
+#[cfg(feature = "testing")]
+pub use crate::testing::{CapturedEvents, MockStep, SagaPath, SagaTestApp};
+pub use crate::timeout::{SuspendedInstances, SuspendingStep, TimedOut, TimeoutStage};
+pub use crate::util::{
+    process_event, EventProcessors, SagaEventReader, SagaEventSet, SharedEvents,
+};
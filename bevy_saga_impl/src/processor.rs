@@ -1,3 +1,4 @@
+use crate::graph::SagaEdgeLabel;
 use crate::{SagaEvent, extension::BevySagaUtil};
 use bevy::ecs::schedule::ScheduleConfigs;
 use bevy::ecs::system::ScheduleSystem;
@@ -14,6 +15,9 @@ use variadics_please::all_tuples;
 /// Both the input and output types have to be attributed with `saga_event`.
 /// 
 /// All systems in the collection are executed concurrently.
+///
+/// To collect the return values of several systems that take the same event, use
+/// [gather](crate::prelude::gather).
 /// 
 /// # Option Processor
 /// 
@@ -23,7 +27,35 @@ use variadics_please::all_tuples;
 /// If the option is Some, the containing value will be passed on to the following processors or 
 /// handler in the saga.
 /// If the option is empty, the following processors or handler in the saga won't be executed.
-/// 
+///
+/// # Iterator Processor
+///
+/// An event processor can also fan out: when it returns anything that implements
+/// [IntoIterator](IntoIterator) over saga events, like a [Vec](Vec), an array or an
+/// [Iterator](Iterator), every item is passed on to the following processors or handler in the
+/// saga. All items belong to the same saga instance. If there are no items, the following
+/// processors or handler in the saga won't be executed. The Option processor is the iterator
+/// processor that returns at most one item.
+///
+/// Like futures, iterators can't borrow from the system parameters of the processor. Collect what
+/// you need or add `+ use<>` to the return type.
+///
+/// # Async Processor
+///
+/// An event processor can also return a [Future](Future) of a saga event. The future is spawned on
+/// the [AsyncComputeTaskPool](bevy::tasks::AsyncComputeTaskPool) and its output is passed on to
+/// the following processors or handler once the task completes, which may be several updates
+/// later. Until then, the saga instance is held in the [PendingTasks](crate::prelude::PendingTasks)
+/// resource. Async processors are [suspending steps](crate::prelude::SuspendingStep), so they can
+/// be given a [timeout](crate::prelude::TimeoutStage).
+///
+/// The future can't borrow from the system parameters of the processor. Copy or clone what you
+/// need into the future and add `+ use<>` to the return type, so the future doesn't capture the
+/// lifetimes of the parameters.
+///
+/// Tasks only make progress when the task pools are ticked. Add the
+/// [TaskPoolPlugin](bevy::app::TaskPoolPlugin), which is part of the default plugins, to your app.
+///
 /// # Example
 ///
 /// ```
@@ -61,6 +93,30 @@ use variadics_please::all_tuples;
 /// 
 /// let processor = (maybe_process_event, sibling1, sibling2);
 /// app.add_saga(Update, (processor, handler));
+///
+/// // A processor that fans out into several events:
+/// fn explode(_: A, /* other queries or resources */) -> Vec<B> {
+///     vec![B, B, B]
+/// }
+///
+/// fn shrapnel(_: A, /* other queries or resources */) -> impl Iterator<Item = B> + use<> {
+///     std::iter::repeat_n(B, 3)
+/// }
+///
+/// fn burst(_: A, /* other queries or resources */) -> [B; 2] {
+///     [B, B]
+/// }
+///
+/// app.add_saga(Update, ((explode, sibling1), handler));
+/// app.add_saga(Update, (shrapnel, handler));
+/// app.add_saga(Update, (burst, handler));
+///
+/// // And an async processor:
+/// fn load(_: A, /* other queries or resources */) -> impl Future<Output = B> + use<> {
+///     async { B }
+/// }
+///
+/// app.add_saga(Update, ((load, sibling1), handler));
 /// ```
 pub trait EventProcessor<M> {
     type In: SagaEvent;
@@ -102,7 +158,7 @@ macro_rules! impl_event_processor {
                 let (proc, $($h,)*) = self;
                 (
                     app.add_event_processor::<In, Out, _>(proc),
-                    $(app.add_event_handler::<In, _>($h),)*
+                    $(app.add_step_handler::<In, _>($h, Some(SagaEdgeLabel::Sibling)),)*
                 )
                     .into_configs()
             }
@@ -0,0 +1,109 @@
+use crate::branches::{Branches, abandon_others};
+use crate::processor::EventProcessor;
+use crate::SagaEvent;
+use bevy::app::App;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::IntoScheduleConfigs;
+use variadics_please::all_tuples;
+
+pub struct Race<T>(T);
+
+pub struct RaceM<T>(T);
+
+macro_rules! impl_race {
+    ($(#[$meta:meta])* $(($B:ident, $b:ident, $M:ident)),*) => {
+        impl<$($B,)* $($M,)* In, Out> EventProcessor<RaceM<($($M,)*)>> for Race<($($B,)*)>
+        where
+            In: SagaEvent,
+            Out: SagaEvent,
+            $($B: EventProcessor<$M, In = In, Out = Out>,)*
+        {
+            type In = In;
+            type Out = Out;
+
+            fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+                let Race(($($b,)*)) = self;
+                let mut branches = Branches::<In, Out, ()>::new(app);
+                let entered = branches.entered();
+                let racing = ($(branches.add_branch(app, $b),)*).into_configs();
+                // The first output of every event wins, and the other branches abandon the event.
+                let gate = branches.finish(app, Some("race"), move |world, event: Out| {
+                    let mut entered = entered.lock().unwrap();
+                    let (instance, branch, group) = entered.current(world)?;
+                    let group = entered.leave(group)?;
+                    drop(entered);
+                    abandon_others(world, group, branch);
+                    Some((instance, event))
+                });
+                (gate, racing).into_configs()
+            }
+        }
+    }
+}
+
+all_tuples!(impl_race, 2, 15, B, b, M);
+
+/// A saga step that sends its event to several branches and continues with the output of the
+/// branch that produces it first.
+///
+/// Every branch is an [event processor](EventProcessor) with the same input and output types.
+/// Branches that suspend the saga instance, like [await_event](crate::prelude::await_event) or an
+/// [async processor](EventProcessor#async-processor), may produce their output several updates
+/// later. The first output for every event is passed on to the following processors or handler.
+/// The losing branches that are still suspended on the event abandon it, so they don't produce an
+/// output and their [timeouts](crate::prelude::TimeoutStage) don't fire. When a saga instance
+/// receives several events, for example from an [iterator processor](EventProcessor#iterator-processor), each of
+/// them is raced separately.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::Entity;
+/// use bevy_saga_impl::prelude::{await_event, race, Awaited, Correlated, LightweightStage};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Turn(Entity);
+///
+/// #[saga_event]
+/// struct PlayerInput(Entity, u8);
+///
+/// #[saga_event]
+/// struct AiDecision(Entity, u8);
+///
+/// #[saga_event]
+/// struct Move(u8);
+///
+/// impl Correlated for Turn {
+///     type Key = Entity;
+///
+///     fn correlation_key(&self) -> Entity { self.0 }
+/// }
+///
+/// impl Correlated for PlayerInput {
+///     type Key = Entity;
+///
+///     fn correlation_key(&self) -> Entity { self.0 }
+/// }
+///
+/// impl Correlated for AiDecision {
+///     type Key = Entity;
+///
+///     fn correlation_key(&self) -> Entity { self.0 }
+/// }
+///
+/// fn make_move(_: Move, /* other queries or resources */) { }
+///
+/// # let mut app = App::new();
+/// // The player moves, unless the AI decides first.
+/// app.add_saga(Update, (
+///     race((
+///         await_event::<Turn, PlayerInput>().map_event(|Awaited(_, input)| Move(input.1)),
+///         await_event::<Turn, AiDecision>().map_event(|Awaited(_, decision)| Move(decision.1)),
+///     )),
+///     make_move,
+/// ));
+/// ```
+pub fn race<T>(branches: T) -> Race<T> {
+    Race(branches)
+}
@@ -0,0 +1,225 @@
+use crate::SagaEvent;
+use bevy::app::{App, First, Last, Plugin};
+use bevy::ecs::error::{BevyError, Result};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::{Command, ResMut, Resource, World};
+use serde::{Deserialize, Serialize};
+use std::any::type_name;
+
+/// An event that entered the sagas from the outside, as it is stored in a [SagaRecording].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// The frame in which the sagas received the event, counted from the start of the recording.
+    pub frame: u32,
+    /// The type name of the event.
+    pub event: String,
+    /// The serialized event.
+    pub value: serde_json::Value,
+}
+
+/// A log of the events that entered the sagas from the outside, recorded by the
+/// [SagaRecorderPlugin] and replayed by the [SagaReplayPlugin].
+///
+/// Events that are produced in a saga are not recorded, because the replayed sagas produce them
+/// again. Only the events that opt into serde with `#[saga_event(serde)]` can be recorded. The
+/// first time another event type enters a saga, an error is passed to Bevy's error handler, because
+/// a replay of the recording would miss those events.
+///
+/// The recording can be written with any serde format, like RON or JSON, and attached to a bug
+/// report.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SagaRecording {
+    events: Vec<RecordedEvent>,
+    #[serde(skip)]
+    frame: u32,
+    #[serde(skip)]
+    skipped: HashSet<&'static str>,
+}
+
+impl SagaRecording {
+    /// The recorded events, in the order the sagas received them.
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// The event types that entered a saga, but weren't recorded because they don't opt into
+    /// serde.
+    pub fn skipped(&self) -> impl Iterator<Item = &'static str> {
+        self.skipped.iter().copied()
+    }
+
+    fn record<R>(&mut self, event: &R) -> Result
+    where
+        R: SagaEvent,
+    {
+        let Some(value) = event.to_record() else {
+            if !self.skipped.insert(type_name::<R>()) {
+                return Ok(());
+            }
+            return Err(BevyError::from(format!(
+                "{} entered a saga, but it can't be recorded, because it doesn't use \
+                 #[saga_event(serde)]. Replays of the recording will miss it.",
+                type_name::<R>()
+            )));
+        };
+        self.events.push(RecordedEvent {
+            frame: self.frame,
+            event: type_name::<R>().to_string(),
+            value,
+        });
+        Ok(())
+    }
+}
+
+/// Records the event in the [SagaRecording], if the [SagaRecorderPlugin] was added.
+pub(crate) fn record_event<R>(event: R) -> impl Command<Result>
+where
+    R: SagaEvent,
+{
+    move |world: &mut World| match world.get_resource_mut::<SagaRecording>() {
+        Some(mut recording) => recording.record(&event),
+        None => Ok(()),
+    }
+}
+
+/// A plugin that records every event that enters the sagas from the outside in the
+/// [SagaRecording], together with the frame in which the sagas received it.
+///
+/// Replay the recording in a fresh app with the same sagas with the [SagaReplayPlugin].
+///
+/// This plugin is only available with the `serde` feature.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// use bevy_saga_impl::prelude::{SagaRecorderPlugin, SagaRecording, SagaReplay, SagaReplayPlugin};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event(serde)]
+/// struct Attack(u8);
+///
+/// fn attack(_: Attack, /* other queries or resources */) { }
+///
+/// let mut app = App::new();
+/// app.add_plugins(SagaRecorderPlugin);
+/// app.add_saga(Update, attack);
+/// app.world_mut().send_event(Attack(1));
+/// app.update();
+///
+/// let log = serde_json::to_string(app.world().resource::<SagaRecording>()).unwrap();
+///
+/// let recording: SagaRecording = serde_json::from_str(&log).unwrap();
+/// let mut replay = App::new();
+/// replay.add_plugins(SagaReplayPlugin::new(recording));
+/// replay.add_saga(Update, attack);
+/// replay.update();
+/// assert!(replay.world().resource::<SagaReplay>().is_finished());
+/// ```
+pub struct SagaRecorderPlugin;
+
+impl Plugin for SagaRecorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SagaRecording>();
+        app.add_systems(Last, count_recorded_frames);
+    }
+}
+
+fn count_recorded_frames(mut recording: ResMut<SagaRecording>) {
+    recording.frame += 1;
+}
+
+/// A plugin that replays a [SagaRecording] by sending every recorded event at the start of the
+/// frame in which the sagas received it.
+///
+/// The app needs the same sagas as the recorded one, and it shouldn't send the recorded events
+/// itself. Replaying an event type that no saga of the app handles, or that can't be deserialized,
+/// fails with an error.
+///
+/// This plugin is only available with the `serde` feature.
+pub struct SagaReplayPlugin {
+    recording: SagaRecording,
+}
+
+impl SagaReplayPlugin {
+    pub fn new(recording: SagaRecording) -> Self {
+        SagaReplayPlugin { recording }
+    }
+}
+
+impl Plugin for SagaReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SagaReplay {
+            events: self.recording.events.clone(),
+            next: 0,
+            frame: 0,
+        });
+        app.init_resource::<SagaReplayers>();
+        app.add_systems(First, replay_events);
+    }
+}
+
+/// A resource used by the [SagaReplayPlugin] to hold the events that were not replayed yet.
+#[derive(Resource)]
+pub struct SagaReplay {
+    events: Vec<RecordedEvent>,
+    next: usize,
+    frame: u32,
+}
+
+impl SagaReplay {
+    /// Whether every recorded event was replayed.
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+}
+
+/// A resource used by bevy_saga to send the replayed events of every type that a saga handles.
+///
+/// It's not recommended to use this resource in your own code.
+#[derive(Resource, Default)]
+pub struct SagaReplayers {
+    replayers: HashMap<&'static str, fn(&mut World, serde_json::Value) -> Result>,
+}
+
+impl SagaReplayers {
+    pub(crate) fn register<R>(&mut self)
+    where
+        R: SagaEvent,
+    {
+        self.replayers.insert(type_name::<R>(), replay_event::<R>);
+    }
+}
+
+fn replay_event<R>(world: &mut World, value: serde_json::Value) -> Result
+where
+    R: SagaEvent,
+{
+    let event = R::from_record(value).ok_or_else(|| {
+        BevyError::from(format!("{} can't be replayed without serde.", type_name::<R>()))
+    })??;
+    world.send_event(event);
+    Ok(())
+}
+
+fn replay_events(world: &mut World) -> Result {
+    let events = {
+        let mut replay = world.resource_mut::<SagaReplay>();
+        let start = replay.next;
+        let frame = replay.frame;
+        let end = start
+            + replay.events[start..]
+                .iter()
+                .take_while(|event| event.frame == frame)
+                .count();
+        replay.next = end;
+        replay.frame += 1;
+        replay.events[start..end].to_vec()
+    };
+    for RecordedEvent { event, value, .. } in events {
+        let replayer = world
+            .get_resource::<SagaReplayers>()
+            .and_then(|replayers| replayers.replayers.get(event.as_str()).copied())
+            .ok_or_else(|| BevyError::from(format!("No saga handles the replayed event {event}.")))?;
+        replayer(world, value)?;
+    }
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use crate::SagaEvent;
 use crate::extension::BevySagaUtil;
+use crate::graph::SagaEdgeLabel;
+use crate::retry::RetryPolicy;
 use bevy::app::App;
 use bevy::ecs::schedule::ScheduleConfigs;
 use bevy::ecs::system::ScheduleSystem;
@@ -12,6 +14,12 @@ pub trait ResultProcessor<M> {
     type Err: Event;
 
     fn register_result_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem>;
+
+    fn register_retrying_result_processor(
+        self,
+        app: &mut App,
+        policy: RetryPolicy,
+    ) -> ScheduleConfigs<ScheduleSystem>;
 }
 
 impl<RS, MRS, In, Ok, Err> ResultProcessor<(MRS,)> for RS
@@ -29,6 +37,14 @@ where
     fn register_result_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
         app.add_result_handler(self)
     }
+
+    fn register_retrying_result_processor(
+        self,
+        app: &mut App,
+        policy: RetryPolicy,
+    ) -> ScheduleConfigs<ScheduleSystem> {
+        app.add_retrying_result_handler(self, policy)
+    }
 }
 
 macro_rules! impl_result_processor {
@@ -51,7 +67,20 @@ macro_rules! impl_result_processor {
                 let (rs, $($rh,)*) = self;
                 (
                     app.add_result_handler(rs),
-                    $(app.add_event_handler($rh),)*
+                    $(app.add_step_handler($rh, Some(SagaEdgeLabel::Sibling)),)*
+                )
+                    .into_configs()
+            }
+
+            fn register_retrying_result_processor(
+                self,
+                app: &mut App,
+                policy: RetryPolicy,
+            ) -> ScheduleConfigs<ScheduleSystem> {
+                let (rs, $($rh,)*) = self;
+                (
+                    app.add_retrying_result_handler(rs, policy),
+                    $(app.add_step_handler($rh, Some(SagaEdgeLabel::Sibling)),)*
                 )
                     .into_configs()
             }
@@ -0,0 +1,307 @@
+use crate::SagaEvent;
+use crate::condition::ConditionalStep;
+use crate::handle::SagaStates;
+use crate::instance::{
+    CurrentSagaInstance, InstanceStore, SagaInstanceId, StepSystem, release_instance,
+};
+use crate::result_processor::ResultProcessor;
+use bevy::app::App;
+use bevy::ecs::error::BevyError;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::{ScheduleSystem, SystemId};
+use bevy::prelude::{Entity, Event, In, Resource, World};
+use std::sync::Arc;
+
+/// Describes how often a [result processor](crate::prelude::OkStage) is retried before its Err
+/// value is propagated through the Err saga.
+///
+/// Every retry runs the processor again with the original input event, in a later update.
+///
+/// ```
+/// use bevy_saga_impl::prelude::RetryPolicy;
+///
+/// // Retry three times, waiting two updates before every retry.
+/// let policy = RetryPolicy::times(3).backoff_frames(2);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub(crate) times: u32,
+    pub(crate) backoff_frames: u32,
+}
+
+impl RetryPolicy {
+    /// Retries the processor up to `times` times. Every retry happens in the next update.
+    pub fn times(times: u32) -> Self {
+        RetryPolicy {
+            times,
+            backoff_frames: 1,
+        }
+    }
+
+    /// Waits `frames` updates before every retry. Retries never happen in the same update, so
+    /// anything lower than one is treated as one.
+    pub fn backoff_frames(self, frames: u32) -> Self {
+        RetryPolicy {
+            backoff_frames: frames.max(1),
+            ..self
+        }
+    }
+}
+
+struct PendingRetry<R> {
+    step: Entity,
+    instance: SagaInstanceId,
+    event: R,
+    retries_left: u32,
+    frames_left: u32,
+}
+
+/// A resource that holds the saga instances that are waiting to retry a result processor with
+/// input `R`.
+#[derive(Resource)]
+pub struct PendingRetries<R> {
+    retries: Vec<PendingRetry<R>>,
+}
+
+impl<R> Default for PendingRetries<R> {
+    fn default() -> Self {
+        PendingRetries { retries: vec![] }
+    }
+}
+
+impl<R> PendingRetries<R> {
+    /// Iterates over all saga instances that are waiting for a retry and their input events.
+    pub fn iter(&self) -> impl Iterator<Item = (SagaInstanceId, &R)> {
+        self.retries
+            .iter()
+            .map(|retry| (retry.instance, &retry.event))
+    }
+
+    /// The number of saga instances that are waiting for a retry.
+    pub fn len(&self) -> usize {
+        self.retries.len()
+    }
+
+    /// Returns true if no saga instance is waiting for a retry.
+    pub fn is_empty(&self) -> bool {
+        self.retries.is_empty()
+    }
+
+    /// Counts down one update for all retries of `step` and returns the ones that are due.
+    fn take_due(&mut self, step: Entity) -> Vec<PendingRetry<R>> {
+        for retry in self.retries.iter_mut().filter(|retry| retry.step == step) {
+            retry.frames_left -= 1;
+        }
+        let (due, pending) = self
+            .retries
+            .drain(..)
+            .partition(|retry| retry.step == step && retry.frames_left == 0);
+        self.retries = pending;
+        due
+    }
+}
+
+impl<R> InstanceStore for PendingRetries<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn holds(&self, instance: SagaInstanceId) -> bool {
+        self.retries.iter().any(|retry| retry.instance == instance)
+    }
+
+    fn cancel(&mut self, instance: SagaInstanceId) {
+        self.retries.retain(|retry| retry.instance != instance);
+    }
+}
+
+/// The systems of one retrying result processor.
+pub(crate) struct RetryingStep<R, Ok, Err>
+where
+    R: SagaEvent,
+    Ok: Event,
+    Err: Event,
+{
+    pub(crate) attempt: SystemId<R, Result<Ok, Err>>,
+    pub(crate) send: SystemId<In<Result<Ok, Err>>>,
+    pub(crate) policy: RetryPolicy,
+}
+
+impl<R, Ok, Err> Clone for RetryingStep<R, Ok, Err>
+where
+    R: SagaEvent,
+    Ok: Event,
+    Err: Event,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R, Ok, Err> Copy for RetryingStep<R, Ok, Err>
+where
+    R: SagaEvent,
+    Ok: Event,
+    Err: Event,
+{
+}
+
+impl<R, Ok, Err> RetryingStep<R, Ok, Err>
+where
+    R: SagaEvent,
+    Ok: Event,
+    Err: Event,
+{
+    /// Runs the result processor for the current saga instance. An Err is only sent when there are
+    /// no retries left, otherwise the retry is scheduled.
+    pub(crate) fn run(
+        &self,
+        world: &mut World,
+        event: R,
+        retries_left: u32,
+    ) -> Result<(), BevyError> {
+        let result = world.run_system_with(self.attempt, event.clone())?;
+        match (result, world.resource::<CurrentSagaInstance>().get()) {
+            (Err(_), Some(instance)) if retries_left > 0 => {
+                world.resource_mut::<PendingRetries<R>>().retries.push(PendingRetry {
+                    step: self.attempt.entity(),
+                    instance,
+                    event,
+                    retries_left: retries_left - 1,
+                    frames_left: self.policy.backoff_frames,
+                });
+            }
+            (result, _) => world.run_system_with(self.send, result)?,
+        }
+        Ok(())
+    }
+
+    /// Runs all retries of this result processor that are due, as the step of the saga that made
+    /// the first attempt.
+    pub(crate) fn retry_due(
+        &self,
+        world: &mut World,
+        first_attempt: &ConditionalStep<R>,
+    ) -> Result<(), BevyError> {
+        let due = world
+            .resource_mut::<PendingRetries<R>>()
+            .take_due(self.attempt.entity());
+        for PendingRetry {
+            instance,
+            event,
+            retries_left,
+            ..
+        } in due
+        {
+            // Instances of disabled or removed sagas finish without retrying.
+            if !world.resource::<SagaStates>().is_enabled(instance.saga()) {
+                release_instance(instance)(world);
+                continue;
+            }
+            let step = *self;
+            let retry = ConditionalStep {
+                system: StepSystem::Inline(Arc::new(move |world: &mut World, event: R| {
+                    step.run(world, event, retries_left)
+                })),
+                ..first_attempt.clone()
+            };
+            let result = retry.run(instance, event)(world);
+            // The retry may have been skipped by a run condition.
+            release_instance(instance)(world);
+            result?;
+        }
+        Ok(())
+    }
+}
+
+struct Retrying<RS> {
+    result_source: RS,
+    policy: RetryPolicy,
+}
+
+pub struct RetryingM<T>(T);
+
+impl<RS, MRS> ResultProcessor<RetryingM<MRS>> for Retrying<RS>
+where
+    RS: ResultProcessor<MRS>,
+{
+    type In = RS::In;
+    type Ok = RS::Ok;
+    type Err = RS::Err;
+
+    fn register_result_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        let Retrying {
+            result_source,
+            policy,
+        } = self;
+        result_source.register_retrying_result_processor(app, policy)
+    }
+
+    fn register_retrying_result_processor(
+        self,
+        app: &mut App,
+        policy: RetryPolicy,
+    ) -> ScheduleConfigs<ScheduleSystem> {
+        self.result_source
+            .register_retrying_result_processor(app, policy)
+    }
+}
+
+/// This trait provides the `retry` method on result processors.
+///
+/// When a retrying result processor returns Err, it is executed again with the original input
+/// event in a later update, according to the [RetryPolicy]. Only when there are no retries left,
+/// the Err value is propagated through the Err saga. The Err values of earlier attempts are
+/// dropped. Siblings of the result processor are only executed once.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// # use bevy::prelude::{Entity, Query, Component};
+/// use bevy_saga_impl::prelude::{ErrStage, OkStage, RetryPolicy, RetryStage};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// # #[derive(Component)]
+/// # struct Weapon;
+/// #[saga_event]
+/// struct Equip(Entity);
+///
+/// #[saga_event]
+/// struct Equipped;
+///
+/// #[saga_event]
+/// struct NoWeapon;
+///
+/// // The weapon might not have been inserted yet.
+/// fn equip(Equip(entity): Equip, query: Query<&Weapon>) -> Result<Equipped, NoWeapon> {
+///     query.get(entity).map(|_| Equipped).map_err(|_| NoWeapon)
+/// }
+///
+/// fn equipped(_: Equipped) { }
+/// fn no_weapon(_: NoWeapon) { }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, equip
+///     .retry(RetryPolicy::times(3).backoff_frames(2))
+///     .ok(equipped)
+///     .err(no_weapon));
+/// ```
+pub trait RetryStage<MRS>: ResultProcessor<MRS> + Sized {
+    fn retry(
+        self,
+        policy: RetryPolicy,
+    ) -> impl ResultProcessor<RetryingM<MRS>, In = Self::In, Ok = Self::Ok, Err = Self::Err>;
+}
+
+impl<RS, MRS> RetryStage<MRS> for RS
+where
+    RS: ResultProcessor<MRS>,
+{
+    fn retry(
+        self,
+        policy: RetryPolicy,
+    ) -> impl ResultProcessor<RetryingM<MRS>, In = Self::In, Ok = Self::Ok, Err = Self::Err> {
+        Retrying {
+            result_source: self,
+            policy,
+        }
+    }
+}
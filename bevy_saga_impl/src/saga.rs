@@ -50,6 +50,9 @@ use bevy::prelude::App;
 /// one update cycle. If you send an event that's further down the chain, that event will still be 
 /// propagated through the saga. In that case the earlier processors are not executed.
 ///
+/// The only exception to the single update cycle are steps that wait for something to happen in a
+/// later update, like [await_event](crate::prelude::await_event) or [join](crate::prelude::join).
+///
 /// - Learn how to write event processors [here](crate::processor::EventProcessor).
 /// - Learn how to write an event handler [here](crate::handler::EventHandler).
 ///
@@ -126,6 +129,18 @@ fn impl_saga() -> proc_macro2::TokenStream {
                     ))
                 }
             }
+
+            impl<#(#processor_generics,)* H, #(#marker_generics,)* MH, In> crate::saga::Saga<crate::condition::ConditionedM<(#(#marker_generics,)* MH)>> for crate::condition::Conditioned<(#(#processor_generics,)* H)>
+            where
+                (#(#processor_generics,)* H): crate::saga::Saga<(#(#marker_generics,)* MH), In = In>,
+                In: crate::SagaEvent,
+            {
+                type In = In;
+
+                fn register(self, app: &mut bevy::prelude::App) -> bevy::ecs::schedule::ScheduleConfigs<bevy::ecs::system::ScheduleSystem> {
+                    self.register_with(app, crate::saga::Saga::register)
+                }
+            }
         }
     }).collect::<Vec<_>>();
 
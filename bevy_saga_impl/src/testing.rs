@@ -0,0 +1,403 @@
+use crate::SagaEvent;
+use crate::graph::short_name;
+use crate::handle::SagaHandle;
+use crate::instance::StepSystem;
+use crate::lifecycle::SagaStepCompleted;
+use crate::util::{EventProcessors, send_response};
+use bevy::app::App;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{Event, ResMut, Resource, SystemParamFunction, Trigger, World};
+use std::any::{Any, TypeId, type_name};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+use variadics_please::all_tuples;
+
+type CapturedEvent = Box<dyn Any + Send + Sync>;
+
+/// A resource used by [SagaTestApp] to collect the events that the steps of the sagas emit and
+/// the steps that ran.
+///
+/// It's not recommended to use this resource in your own code.
+#[derive(Resource, Default)]
+pub struct CapturedEvents {
+    events: HashMap<TypeId, Vec<CapturedEvent>>,
+    /// Clones the events of the types that are captured.
+    cloners: HashMap<TypeId, fn(&dyn Any) -> CapturedEvent>,
+    /// The events that the steps wrote in the current update, in the order they were written.
+    written: Mutex<Vec<(TypeId, CapturedEvent)>>,
+    path: Vec<String>,
+}
+
+impl CapturedEvents {
+    /// Captures the events of type `R` from now on.
+    pub(crate) fn register<R>(&mut self)
+    where
+        R: SagaEvent,
+    {
+        self.cloners.insert(TypeId::of::<R>(), |event| {
+            Box::new(event.downcast_ref::<R>().cloned().unwrap())
+        });
+    }
+
+    pub(crate) fn capture<R>(&mut self, event: R)
+    where
+        R: SagaEvent,
+    {
+        self.register::<R>();
+        self.events
+            .entry(TypeId::of::<R>())
+            .or_default()
+            .push(Box::new(event));
+    }
+
+    /// Captures an event that a step wrote, if its type is captured. Steps only have an [Event],
+    /// which can't be cloned, so the type needs to be registered up front.
+    pub(crate) fn capture_written<R>(&self, event: &R)
+    where
+        R: Event,
+    {
+        if let Some(clone) = self.cloners.get(&TypeId::of::<R>()) {
+            self.written.lock().unwrap().push((TypeId::of::<R>(), clone(event)));
+        }
+    }
+
+    /// Moves the events that were written in the update to the captured events.
+    fn collect(&mut self) {
+        for (event_type, event) in self.written.get_mut().unwrap().drain(..) {
+            self.events.entry(event_type).or_default().push(event);
+        }
+    }
+
+    fn of<R>(&self) -> impl Iterator<Item = &R>
+    where
+        R: SagaEvent,
+    {
+        self.events
+            .get(&TypeId::of::<R>())
+            .into_iter()
+            .flatten()
+            .filter_map(|event| event.downcast_ref::<R>())
+    }
+}
+
+/// The steps of a saga path, for [assert_path](SagaTestApp::assert_path).
+///
+/// This trait is implemented for arrays and vectors of step names, and for tuples of up to 15
+/// processor and handler functions. You don't have to implement it yourself.
+pub trait SagaPath<M> {
+    /// The names of the steps, as they appear in the [SagaGraph](crate::prelude::SagaGraph).
+    fn step_names(self) -> Vec<String>;
+}
+
+impl<const N: usize> SagaPath<()> for [&str; N] {
+    fn step_names(self) -> Vec<String> {
+        self.iter().map(|step| step.to_string()).collect()
+    }
+}
+
+impl SagaPath<()> for Vec<&str> {
+    fn step_names(self) -> Vec<String> {
+        self.iter().map(|step| step.to_string()).collect()
+    }
+}
+
+macro_rules! impl_saga_path {
+    ($(#[$meta:meta])* $(($F:ident, $M:ident)),*) => {
+        impl<$($F,)* $($M,)*> SagaPath<($($M,)*)> for ($($F,)*)
+        where
+            $($F: SystemParamFunction<$M>,)*
+        {
+            fn step_names(self) -> Vec<String> {
+                vec![$(short_name(type_name::<$F>()),)*]
+            }
+        }
+    }
+}
+
+all_tuples!(impl_saga_path, 1, 15, F, M);
+
+/// This trait provides the `mock_step` method on the [App] and the [SagaTestApp].
+///
+/// A mock replaces the processors that were already added to a saga, so a step downstream of them
+/// can be tested without defining the saga again. The mock receives the input event of the
+/// processors and returns their output event. It replaces the processors in the saga of the
+/// [SagaHandle], together with their siblings that produce the same output. Other sagas keep their
+/// processors. The run conditions of the processors still apply, but their systems are
+/// unregistered.
+///
+/// This trait is only available with the `testing` feature.
+///
+/// ```
+/// # use bevy::app::Update;
+/// # use bevy::prelude::Entity;
+/// use bevy_saga_impl::prelude::{MockStep, SagaTestApp};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct AttackTrigger(Entity);
+///
+/// #[saga_event]
+/// struct Offense(u8);
+///
+/// fn calculate_offense(_: AttackTrigger, /* queries */) -> Offense {
+///     Offense(0)
+/// }
+///
+/// fn take_damage(_: Offense, /* other queries or resources */) { }
+///
+/// let mut app = SagaTestApp::new();
+/// let saga = app.add_saga(Update, (calculate_offense, take_damage));
+/// app.mock_step::<AttackTrigger, Offense>(saga, |_| Offense(5));
+/// app.send(AttackTrigger(Entity::PLACEHOLDER)).update();
+///
+/// app.assert_emitted::<Offense>(|offense| offense.0 == 5);
+/// ```
+pub trait MockStep {
+    /// Replaces the processors of `R` in the saga that produce `Rs` with the mock.
+    ///
+    /// # Panics
+    ///
+    /// Panics when no processor of `R` in the saga produces `Rs`.
+    fn mock_step<R, Rs>(
+        &mut self,
+        saga: SagaHandle,
+        mock: impl Fn(R) -> Rs + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        R: SagaEvent,
+        Rs: SagaEvent;
+}
+
+impl MockStep for App {
+    fn mock_step<R, Rs>(
+        &mut self,
+        saga: SagaHandle,
+        mock: impl Fn(R) -> Rs + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        R: SagaEvent,
+        Rs: SagaEvent,
+    {
+        let system = StepSystem::Inline(Arc::new(move |world: &mut World, event: R| {
+            world.run_system_cached_with(send_response::<Rs>, mock(event))?;
+            Ok(())
+        }));
+        let replaced = self
+            .world_mut()
+            .get_resource_mut::<EventProcessors<R>>()
+            .map(|mut processors| processors.replace(saga.id(), TypeId::of::<Rs>(), system))
+            .unwrap_or_default();
+        assert!(
+            !replaced.is_empty(),
+            "No processor of {} in the saga produces {}.",
+            type_name::<R>(),
+            type_name::<Rs>(),
+        );
+        for replaced in replaced {
+            if let StepSystem::Registered(system) = replaced {
+                let _ = self.world_mut().unregister_system(system);
+            }
+        }
+        self
+    }
+}
+
+impl MockStep for SagaTestApp {
+    fn mock_step<R, Rs>(
+        &mut self,
+        saga: SagaHandle,
+        mock: impl Fn(R) -> Rs + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        R: SagaEvent,
+        Rs: SagaEvent,
+    {
+        self.app.mock_step(saga, mock);
+        self
+    }
+}
+
+/// An [App] for testing sagas, which captures every event that the steps of the sagas emit, the
+/// events that are sent with [send](SagaTestApp::send), and every step that runs.
+///
+/// It dereferences to the App, so sagas are added and updated like in any other app. The
+/// assertions panic with a message that lists what was captured instead. Events are captured when
+/// a step emits them. The event types that sagas handle are captured automatically. Other event
+/// types, like the Err values of a result processor without an Err saga, are only captured after
+/// [capture](SagaTestApp::capture) is called for them. The outputs of
+/// [lightweight steps](crate::prelude::LightweightStage) that are handed directly to the following
+/// steps are not emitted, so they are not captured.
+///
+/// This struct is only available with the `testing` feature.
+///
+/// ```
+/// # use bevy::app::Update;
+/// use bevy_saga_impl::prelude::SagaTestApp;
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct Attack(u8);
+///
+/// #[saga_event]
+/// struct Damage(u8);
+///
+/// #[saga_event]
+/// struct Missed;
+///
+/// fn attack(Attack(power): Attack) -> Damage { Damage(power * 2) }
+/// fn take_damage(_: Damage, /* other queries or resources */) { }
+///
+/// let mut app = SagaTestApp::new();
+/// app.add_saga(Update, (attack, take_damage));
+/// app.capture::<Missed>();
+/// app.send(Attack(1)).update();
+///
+/// app.assert_emitted::<Damage>(|damage| damage.0 == 2);
+/// app.assert_not_emitted::<Missed>();
+/// app.assert_path((attack, take_damage));
+/// ```
+pub struct SagaTestApp {
+    app: App,
+}
+
+impl Default for SagaTestApp {
+    fn default() -> Self {
+        SagaTestApp::new()
+    }
+}
+
+impl SagaTestApp {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.init_resource::<CapturedEvents>();
+        app.add_observer(|step: Trigger<SagaStepCompleted>, mut captured: ResMut<CapturedEvents>| {
+            captured.path.push(step.step.to_string());
+        });
+        SagaTestApp { app }
+    }
+
+    /// Sends the event, so it enters the sagas in the next update.
+    pub fn send<R>(&mut self, event: R) -> &mut Self
+    where
+        R: SagaEvent,
+    {
+        let world = self.app.world_mut();
+        world.resource_mut::<CapturedEvents>().capture(event.clone());
+        world.send_event(event);
+        self
+    }
+
+    /// Captures the events of type `R` that the steps emit, even though no saga handles them.
+    pub fn capture<R>(&mut self) -> &mut Self
+    where
+        R: SagaEvent,
+    {
+        self.app.world_mut().resource_mut::<CapturedEvents>().register::<R>();
+        self
+    }
+
+    /// Runs the app once.
+    pub fn update(&mut self) -> &mut Self {
+        self.app.update();
+        self.app.world_mut().resource_mut::<CapturedEvents>().collect();
+        self
+    }
+
+    /// The captured events of type `R`, in the order they were emitted.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the events of type `R` aren't captured, because no saga handles them and
+    /// [capture](SagaTestApp::capture) wasn't called for them.
+    pub fn emitted<R>(&self) -> Vec<&R>
+    where
+        R: SagaEvent,
+    {
+        let captured = self.captured();
+        assert!(
+            captured.cloners.contains_key(&TypeId::of::<R>()),
+            "Events of type {} are not captured, because no saga handles them. Call capture \
+             before the update to capture them.",
+            type_name::<R>(),
+        );
+        captured.of::<R>().collect()
+    }
+
+    /// The names of the steps that ran, in the order they ran.
+    pub fn path(&self) -> &[String] {
+        &self.captured().path
+    }
+
+    /// Forgets the captured events and steps.
+    pub fn clear(&mut self) {
+        let mut captured = self.app.world_mut().resource_mut::<CapturedEvents>();
+        captured.events.clear();
+        captured.path.clear();
+    }
+
+    /// Asserts that an event of type `R` that matches the predicate was captured.
+    ///
+    /// # Panics
+    ///
+    /// Panics when no captured event of type `R` matches, or when the events of type `R` aren't
+    /// captured.
+    pub fn assert_emitted<R>(&self, predicate: impl Fn(&R) -> bool)
+    where
+        R: SagaEvent,
+    {
+        let emitted = self.emitted::<R>();
+        assert!(
+            emitted.iter().any(|event| predicate(event)),
+            "Expected an event of type {} that matches the predicate, but none of the {} captured \
+             events of that type did.",
+            type_name::<R>(),
+            emitted.len(),
+        );
+    }
+
+    /// Asserts that no event of type `R` was captured.
+    ///
+    /// # Panics
+    ///
+    /// Panics when an event of type `R` was captured, or when the events of type `R` aren't
+    /// captured.
+    pub fn assert_not_emitted<R>(&self)
+    where
+        R: SagaEvent,
+    {
+        let emitted = self.emitted::<R>().len();
+        assert_eq!(
+            emitted,
+            0,
+            "Expected no event of type {}, but {emitted} were captured.",
+            type_name::<R>(),
+        );
+    }
+
+    /// Asserts that exactly these steps ran, in this order.
+    ///
+    /// # Panics
+    ///
+    /// Panics when other steps ran, or when they ran in another order.
+    pub fn assert_path<M>(&self, path: impl SagaPath<M>) {
+        assert_eq!(self.path(), path.step_names(), "The steps that ran differ from the path.");
+    }
+
+    fn captured(&self) -> &CapturedEvents {
+        self.app.world().resource::<CapturedEvents>()
+    }
+}
+
+impl Deref for SagaTestApp {
+    type Target = App;
+
+    fn deref(&self) -> &App {
+        &self.app
+    }
+}
+
+impl DerefMut for SagaTestApp {
+    fn deref_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
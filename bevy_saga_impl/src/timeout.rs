@@ -0,0 +1,267 @@
+use crate::instance::{InstanceStore, SagaId, SagaInstance, SagaInstanceId, SagaWriter};
+use crate::processor::EventProcessor;
+use crate::saga::Saga;
+use crate::branches::Branches;
+use crate::extension::SagaRegistrations;
+use crate::util::SagaFlowSet;
+use crate::{SagaEvent, extension::BevySagaUtil};
+use bevy::app::App;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::{Event, IntoScheduleConfigs, Res, ResMut, Resource, SystemInput};
+use bevy::time::{Time, Virtual};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+/// A saga step that suspends saga instances until something happens in a later update.
+///
+/// Suspending steps can be given a [timeout](TimeoutStage::timeout). These are
+/// [await_event](crate::prelude::await_event), [join](crate::prelude::join),
+/// [async processors](crate::prelude::PendingTasks), and suspending steps with a
+/// [run condition](crate::prelude::RunIfStage).
+pub trait SuspendingStep<M>: EventProcessor<M> {
+    /// The resource that holds the saga instances while they are suspended.
+    type Suspended: SuspendedInstances;
+}
+
+/// A resource that holds suspended saga instances.
+pub trait SuspendedInstances: InstanceStore {
+    /// Drops a suspended saga instance without resuming it.
+    fn abandon(&mut self, instance: SagaInstanceId);
+
+    /// When the suspended saga instance started waiting, in [virtual time](Virtual), if that was
+    /// before it was suspended. The deadline of a [timeout](TimeoutStage) starts at that moment.
+    fn waiting_since(&self, _instance: SagaInstanceId) -> Option<Duration> {
+        None
+    }
+
+    /// Drops what waits for the saga instances of the saga without belonging to one of them, if
+    /// it arrived at or before `since`, in [virtual time](Virtual). The [timeout](TimeoutStage)
+    /// of the step calls this, so it doesn't wait longer than the saga instances would.
+    fn expire(&mut self, _saga: SagaId, _since: Duration) {}
+}
+
+/// The event that is propagated through the timeout saga of a [suspending step](SuspendingStep)
+/// when it didn't resume in time.
+///
+/// It holds the event the step was started with.
+#[derive(Clone)]
+pub struct TimedOut<A>(pub A);
+
+impl<A> Event for TimedOut<A>
+where
+    A: SagaEvent,
+{
+    type Traversal = ();
+}
+
+impl<A> SystemInput for TimedOut<A>
+where
+    A: SagaEvent,
+{
+    type Param<'i> = TimedOut<A>;
+    type Inner<'i> = TimedOut<A>;
+
+    fn wrap(this: Self::Inner<'_>) -> Self::Param<'_> {
+        this
+    }
+}
+
+impl<A> SagaEvent for TimedOut<A> where A: SagaEvent {}
+
+/// A resource used by bevy_saga to remember when suspended saga instances time out.
+///
+/// It's not recommended to use this resource in your own code.
+#[derive(Resource)]
+pub struct Deadlines<A, S> {
+    deadlines: Vec<Deadline<A>>,
+    _marker: PhantomData<fn() -> S>,
+}
+
+struct Deadline<A> {
+    /// When the saga instance entered the step.
+    suspended: Duration,
+    duration: Duration,
+    instance: SagaInstanceId,
+    event: A,
+}
+
+impl<A, S> Default for Deadlines<A, S> {
+    fn default() -> Self {
+        Deadlines {
+            deadlines: vec![],
+            _marker: PhantomData,
+        }
+    }
+}
+
+fn expire_deadlines<A, S>(
+    time: Res<Time<Virtual>>,
+    mut deadlines: ResMut<Deadlines<A, S>>,
+    mut suspended: ResMut<S>,
+    mut writer: SagaWriter<TimedOut<A>>,
+) where
+    A: SagaEvent,
+    S: SuspendedInstances,
+{
+    let now = time.elapsed();
+    // Instances that are no longer suspended have resumed in time.
+    deadlines
+        .deadlines
+        .retain(|deadline| suspended.holds(deadline.instance));
+    let (expired, pending) = deadlines.deadlines.drain(..).partition(|deadline| {
+        // Some steps wait for other events, which may have arrived before the instance.
+        let waiting_since = suspended
+            .waiting_since(deadline.instance)
+            .map_or(deadline.suspended, |since| since.min(deadline.suspended));
+        waiting_since + deadline.duration <= now
+    });
+    deadlines.deadlines = pending;
+    for Deadline { instance, event, .. } in expired {
+        suspended.abandon(instance);
+        writer.write_as(instance, TimedOut(event));
+    }
+}
+
+struct Timeout<Step, TimeoutSaga> {
+    step: Step,
+    duration: Duration,
+    timeout_saga: TimeoutSaga,
+}
+
+pub struct TimeoutM<T>(T);
+
+impl<Step, TimeoutSaga, MS, MT> EventProcessor<TimeoutM<(MS, MT)>> for Timeout<Step, TimeoutSaga>
+where
+    Step: SuspendingStep<MS>,
+    Step::Out: SagaEvent,
+    TimeoutSaga: Saga<MT, In = TimedOut<Step::In>>,
+{
+    type In = Step::In;
+    type Out = Step::Out;
+
+    fn register_processor(self, app: &mut App) -> ScheduleConfigs<ScheduleSystem> {
+        let Timeout {
+            step,
+            duration,
+            timeout_saga,
+        } = self;
+        let graph_step = app.record_saga_step::<Step::In>("timeout", None);
+        app.record_saga_flow::<Step::In, TimedOut<Step::In>>(graph_step, None);
+        app.init_resource::<Time<Virtual>>();
+        app.init_resource::<Deadlines<Step::In, Step::Suspended>>();
+        let timeout_saga = timeout_saga.register(app);
+        // Every event runs the step as an entry of its own, so its deadline doesn't concern the
+        // other events of the saga instance.
+        let mut branches = Branches::<Step::In, Step::Out, ()>::new(app);
+        let entered = branches.entered();
+        let step = branches.add_branch(app, step);
+        let record_deadline = app.add_event_handler(
+            move |event: Step::In,
+                  instance: SagaInstance,
+                  time: Res<Time<Virtual>>,
+                  mut deadlines: ResMut<Deadlines<Step::In, Step::Suspended>>| {
+                deadlines.deadlines.push(Deadline {
+                    suspended: time.elapsed(),
+                    duration,
+                    instance: instance.current(),
+                    event,
+                });
+            },
+        );
+        let saga = app.world_mut().resource_mut::<SagaRegistrations>().current();
+        let expire = move |time: Res<Time<Virtual>>, mut suspended: ResMut<Step::Suspended>| {
+            if let Some(since) = time.elapsed().checked_sub(duration) {
+                suspended.expire(saga, since);
+            }
+        };
+        let resume = branches.finish(app, None, move |world, event: Step::Out| {
+            let (instance, _, _) = entered.lock().unwrap().current(world)?;
+            Some((instance, event))
+        });
+        (
+            (expire_deadlines::<Step::In, Step::Suspended>, expire)
+                .chain()
+                .before(SagaFlowSet::of::<TimedOut<Step::In>>(saga)),
+            timeout_saga,
+            step,
+            record_deadline,
+            resume,
+        )
+            .into_configs()
+    }
+}
+
+/// This trait provides the `timeout` method on [suspending steps](SuspendingStep).
+///
+/// Other steps, like plain processors and handlers, can't be given a timeout. They run in the same
+/// update as the event they receive, so they never wait for a deadline.
+///
+/// When a saga instance is still suspended after the given duration, it is dropped from the step.
+/// Instead, a [TimedOut] event that holds the event the step was started with is propagated
+/// through the timeout saga. This works the same way as the Err saga of a
+/// [result handler](crate::prelude::ErrStage). When a saga instance receives several events, for
+/// example from an [iterator processor](EventProcessor#iterator-processor), every event has a
+/// deadline of its own, and only the events that are still suspended time out.
+///
+/// The duration is measured in [virtual time](Virtual), so it pauses when the game is paused.
+///
+/// ```
+/// # use bevy::app::{App, Update};
+/// use std::time::Duration;
+/// use bevy_saga_impl::prelude::{await_event, Awaited, Correlated, TimedOut, TimeoutStage};
+/// # use bevy_saga_impl::SagaRegistry;
+/// # use bevy_saga_macros::saga_event;
+/// #[saga_event]
+/// struct DialogOpened(u8);
+///
+/// #[saga_event]
+/// struct Choice(u8);
+/// # impl Correlated for DialogOpened {
+/// #     type Key = u8;
+/// #     fn correlation_key(&self) -> u8 { self.0 }
+/// # }
+/// # impl Correlated for Choice {
+/// #     type Key = u8;
+/// #     fn correlation_key(&self) -> u8 { self.0 }
+/// # }
+///
+/// fn continue_dialog(_: Awaited<DialogOpened, Choice>) { }
+/// fn close_dialog(TimedOut(opened): TimedOut<DialogOpened>) { }
+///
+/// # let mut app = App::new();
+/// app.add_saga(Update, (
+///     await_event::<DialogOpened, Choice>().timeout(Duration::from_secs(30), close_dialog),
+///     continue_dialog,
+/// ));
+/// ```
+pub trait TimeoutStage<MS>: SuspendingStep<MS> + Sized {
+    fn timeout<TimeoutSaga, MT>(
+        self,
+        duration: Duration,
+        timeout_saga: TimeoutSaga,
+    ) -> impl EventProcessor<TimeoutM<(MS, MT)>, In = Self::In, Out = Self::Out>
+    where
+        TimeoutSaga: Saga<MT, In = TimedOut<Self::In>>;
+}
+
+impl<Step, MS> TimeoutStage<MS> for Step
+where
+    Step: SuspendingStep<MS>,
+    Step::Out: SagaEvent,
+{
+    fn timeout<TimeoutSaga, MT>(
+        self,
+        duration: Duration,
+        timeout_saga: TimeoutSaga,
+    ) -> impl EventProcessor<TimeoutM<(MS, MT)>, In = Self::In, Out = Self::Out>
+    where
+        TimeoutSaga: Saga<MT, In = TimedOut<Self::In>>,
+    {
+        Timeout {
+            step: self,
+            duration,
+            timeout_saga,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::ecs::system::System;
+
+/// Enters a span around every run of the system it adapts.
+#[cfg(feature = "tracing")]
+pub(crate) struct Traced {
+    span: tracing::Span,
+}
+
+#[cfg(feature = "tracing")]
+impl<S> bevy::ecs::system::Adapt<S> for Traced
+where
+    S: System,
+{
+    type In = S::In;
+    type Out = S::Out;
+
+    fn adapt(
+        &mut self,
+        input: bevy::ecs::system::SystemIn<'_, S>,
+        run_system: impl FnOnce(bevy::ecs::system::SystemIn<'_, S>) -> S::Out,
+    ) -> S::Out {
+        let _entered = self.span.enter();
+        run_system(input)
+    }
+}
+
+/// Wraps the system of a processor or handler in a span that is named after the system and its
+/// input and output types, so the steps of a saga show up in trace captures.
+#[cfg(feature = "tracing")]
+pub(crate) fn traced<S>(system: S) -> bevy::ecs::system::AdapterSystem<Traced, S>
+where
+    S: System,
+{
+    use crate::graph::short_name;
+    let name = system.name();
+    let span = tracing::info_span!(
+        "saga step",
+        name = %short_name(&name),
+        input = %short_name(std::any::type_name::<S::In>()),
+        output = %short_name(std::any::type_name::<S::Out>()),
+    );
+    bevy::ecs::system::AdapterSystem::new(Traced { span }, system, name)
+}
+
+/// Returns the system unchanged, because the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn traced<S>(system: S) -> S
+where
+    S: System,
+{
+    system
+}
@@ -1,6 +1,25 @@
 use crate::SagaEvent;
-use bevy::ecs::system::SystemId;
-use bevy::prelude::{Commands, Event, EventWriter, Events, In, Res, ResMut, Resource};
+use crate::compensation::compensate;
+use crate::condition::{ConditionalStep, cache_conditions, forget_conditions};
+use crate::diagnostics::{SagaDiagnostics, SagaStepMetric};
+#[cfg(feature = "tracing")]
+use crate::graph::short_name;
+use crate::handle::SagaStates;
+use crate::instance::{
+    CurrentSagaInstance, EventInstances, SagaId, SagaInstances, SagaWriter, StepSystem,
+    own_instance, release_instance,
+};
+use crate::lifecycle::{SagaFailed, SagaStarted, emit};
+#[cfg(feature = "serde")]
+use crate::record::record_event;
+use bevy::ecs::error::BevyError;
+use bevy::ecs::event::EventCursor;
+use bevy::ecs::system::{SystemId, SystemParam};
+use bevy::prelude::{Commands, Event, Events, In, Res, ResMut, Resource, SystemSet, World};
+use std::any::{TypeId, type_name};
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 
 /// A resource used by bevy_saga to save the SystemIds of your event processors and handlers.
 ///
@@ -11,7 +30,8 @@ pub struct EventProcessors<R>
 where
     R: SagaEvent,
 {
-    ids: Vec<SystemId<R, ()>>,
+    steps: Vec<(SagaId, StepFeed, ConditionalStep<R>)>,
+    consumers: Vec<(SagaId, SystemId<R, ()>)>,
 }
 
 impl<R> Default for EventProcessors<R>
@@ -19,7 +39,10 @@ where
     R: SagaEvent,
 {
     fn default() -> Self {
-        EventProcessors { ids: vec![] }
+        EventProcessors {
+            steps: vec![],
+            consumers: vec![],
+        }
     }
 }
 
@@ -27,8 +50,249 @@ impl<R> EventProcessors<R>
 where
     R: SagaEvent,
 {
-    pub fn push(&mut self, system_id: SystemId<R, ()>) {
-        self.ids.push(system_id)
+    pub fn push(&mut self, saga: SagaId, system_id: SystemId<R, ()>) {
+        self.push_step(
+            saga,
+            StepFeed::default(),
+            ConditionalStep {
+                system: StepSystem::Registered(system_id),
+                conditions: vec![],
+                name: None,
+                outputs: vec![],
+            },
+        )
+    }
+
+    pub(crate) fn push_step(&mut self, saga: SagaId, feed: StepFeed, step: ConditionalStep<R>) {
+        self.steps.push((saga, feed, step))
+    }
+
+    /// Adds a system that receives every event of type `R`, without starting or continuing a saga
+    /// instance. Steps that resume suspended saga instances by their correlation key, like
+    /// [await_event](crate::prelude::await_event), consume their events this way.
+    pub(crate) fn push_consumer(&mut self, saga: SagaId, system: SystemId<R, ()>) {
+        self.consumers.push((saga, system))
+    }
+
+    /// Replaces the systems of the processors of the saga that produce the event type `output`.
+    /// Returns the systems that were replaced.
+    #[cfg(feature = "testing")]
+    pub(crate) fn replace(
+        &mut self,
+        saga: SagaId,
+        output: TypeId,
+        system: StepSystem<R>,
+    ) -> Vec<StepSystem<R>> {
+        let mut replaced = vec![];
+        for (handler, _, step) in &mut self.steps {
+            if *handler == saga && step.outputs.contains(&output) {
+                replaced.push(std::mem::replace(&mut step.system, system.clone()));
+            }
+        }
+        replaced
+    }
+
+    /// The processor or handler that was added last.
+    pub(crate) fn last(&self) -> Option<&ConditionalStep<R>> {
+        self.steps.last().map(|(_, _, step)| step)
+    }
+
+    /// Forgets the processors and handlers of the saga and returns them.
+    pub(crate) fn remove(&mut self, saga: SagaId) -> Vec<ConditionalStep<R>> {
+        let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.steps)
+            .into_iter()
+            .partition(|(handler, _, _)| *handler == saga);
+        self.steps = kept;
+        removed.into_iter().map(|(_, _, step)| step).collect()
+    }
+
+    /// Forgets the consumers of the saga and returns their systems.
+    pub(crate) fn remove_consumers(&mut self, saga: SagaId) -> Vec<SystemId<R, ()>> {
+        let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.consumers)
+            .into_iter()
+            .partition(|(consumer, _)| *consumer == saga);
+        self.consumers = kept;
+        removed.into_iter().map(|(_, system)| system).collect()
+    }
+
+    /// All sagas that have processors or handlers for `R`, in the order they were added.
+    fn sagas(&self) -> Vec<SagaId> {
+        let mut sagas: Vec<SagaId> = vec![];
+        for (saga, _, _) in &self.steps {
+            if !sagas.contains(saga) {
+                sagas.push(*saga);
+            }
+        }
+        sagas
+    }
+
+    fn handles(&self, saga: SagaId) -> bool {
+        self.steps.iter().any(|(handler, _, _)| *handler == saga)
+    }
+
+    /// The processors and handlers of the saga that receive the events of type `R`.
+    fn of(&self, saga: SagaId) -> impl Iterator<Item = &ConditionalStep<R>> {
+        self.steps
+            .iter()
+            .filter(move |(handler, feed, _)| *handler == saga && feed.events)
+            .map(|(_, _, step)| step)
+    }
+
+    /// The processors and handlers of the saga that follow the lightweight step.
+    pub(crate) fn fed_by(&self, saga: SagaId, inline: u64) -> impl Iterator<Item = &ConditionalStep<R>> {
+        self.steps
+            .iter()
+            .filter(move |(handler, feed, _)| *handler == saga && feed.inline == Some(inline))
+            .map(|(_, _, step)| step)
+    }
+}
+
+/// Describes where a processor or handler gets its input from.
+///
+/// Lightweight steps, like [map_event](crate::prelude::LightweightStage::map_event), hand their
+/// output directly to the steps that follow them, without sending it as an event. When a
+/// lightweight step produces the same event type it receives, the steps that follow it only
+/// receive its output.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct StepFeed {
+    pub(crate) events: bool,
+    pub(crate) inline: Option<u64>,
+}
+
+impl Default for StepFeed {
+    fn default() -> Self {
+        StepFeed {
+            events: true,
+            inline: None,
+        }
+    }
+}
+
+/// The system set that holds the systems that propagate events of type `R` through the sagas.
+///
+/// All sagas share the set, so it can be used to order your own systems before or after the
+/// propagation of `R`. Every event of type `R` is propagated by the first system in the set that
+/// runs. Within one saga, the systems run after the systems of the events that produce `R` in that
+/// saga. The sets are not ordered across sagas, because events that are produced by a saga only
+/// continue in that saga.
+#[derive(SystemSet)]
+pub struct SagaEventSet<R>(PhantomData<fn() -> R>);
+
+impl<R> Default for SagaEventSet<R> {
+    fn default() -> Self {
+        SagaEventSet(PhantomData)
+    }
+}
+
+impl<R> Clone for SagaEventSet<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for SagaEventSet<R> {}
+
+impl<R> PartialEq for SagaEventSet<R> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<R> Eq for SagaEventSet<R> {}
+
+impl<R> Hash for SagaEventSet<R> {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl<R> Debug for SagaEventSet<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SagaEventSet<{}>", std::any::type_name::<R>())
+    }
+}
+
+/// The system set that holds the systems that propagate events of one type for one saga.
+///
+/// The sets of a saga are ordered by how its events flow into each other. Different sagas may use
+/// the same event types in a different order, so the sets are not ordered across sagas.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct SagaFlowSet {
+    saga: SagaId,
+    event: TypeId,
+    name: &'static str,
+}
+
+impl SagaFlowSet {
+    pub(crate) fn of<R>(saga: SagaId) -> Self
+    where
+        R: 'static,
+    {
+        SagaFlowSet {
+            saga,
+            event: TypeId::of::<R>(),
+            name: type_name::<R>(),
+        }
+    }
+}
+
+/// A resource that tells bevy_saga to read events of type `R` without draining them.
+///
+/// Add it with [share_saga_events](crate::SagaRegistry::share_saga_events). It holds the cursor up
+/// to which the sagas have read the events.
+#[derive(Resource)]
+pub struct SharedEvents<R>
+where
+    R: SagaEvent,
+{
+    cursor: EventCursor<R>,
+}
+
+impl<R> Default for SharedEvents<R>
+where
+    R: SagaEvent,
+{
+    fn default() -> Self {
+        SharedEvents {
+            cursor: EventCursor::default(),
+        }
+    }
+}
+
+/// A system parameter used by bevy_saga to read the events of type `R` that are propagated through
+/// the sagas.
+///
+/// It's not recommended to use this system parameter in your own code.
+#[derive(SystemParam)]
+pub struct SagaEventReader<'w, R>
+where
+    R: SagaEvent,
+{
+    events: ResMut<'w, Events<R>>,
+    shared: Option<ResMut<'w, SharedEvents<R>>>,
+}
+
+impl<R> SagaEventReader<'_, R>
+where
+    R: SagaEvent,
+{
+    /// Reads the events that were not propagated yet, together with their ids.
+    fn read(&mut self) -> Vec<(usize, R)> {
+        match &mut self.shared {
+            Some(shared) => shared
+                .cursor
+                .read_with_id(&self.events)
+                .map(|(event, event_id)| (event_id.id, event.clone()))
+                .collect(),
+            None => {
+                // Draining returns the events oldest first, so their ids count up from the oldest
+                // event.
+                let oldest_event = self.events.oldest_event_count();
+                self.events
+                    .drain()
+                    .enumerate()
+                    .map(|(offset, event)| (oldest_event + offset, event))
+                    .collect()
+            }
+        }
     }
 }
 
@@ -37,46 +301,164 @@ where
 /// It is not recommended to use this system in your own code. It's exported from the crate for the
 /// `#[saga_router]` macro.
 pub fn process_event<R>(
-    mut reader: ResMut<Events<R>>,
+    mut reader: SagaEventReader<R>,
     handler: Res<EventProcessors<R>>,
+    mut event_instances: ResMut<EventInstances<R>>,
+    mut saga_instances: ResMut<SagaInstances>,
+    states: Res<SagaStates>,
     mut commands: Commands,
 ) where
     R: SagaEvent,
 {
-    for event in reader.drain() {
-        for id in &handler.ids {
-            commands.run_system_with(*id, event.clone())
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("saga event", event = %short_name(type_name::<R>())).entered();
+    let events = reader.read();
+    // The steps of cancelled saga instances that were already running have finished.
+    saga_instances.clear_cancelled();
+    // Run conditions are evaluated once for all steps and instances that process these events.
+    commands.queue(cache_conditions);
+    for (event_id, event) in events {
+        if event_instances.take_cancelled(event_id) {
+            continue;
+        }
+        // Events that were produced in a saga only continue in that saga. Events from the outside,
+        // or from a saga that doesn't handle them, start an instance of every enabled saga that
+        // does.
+        let instances = match event_instances.remove(event_id) {
+            Some(instance) if handler.handles(instance.saga()) => vec![instance],
+            previous => {
+                match previous {
+                    Some(instance) => commands.queue(release_instance(instance)),
+                    #[cfg(feature = "serde")]
+                    None => commands.queue(record_event(event.clone())),
+                    #[cfg(not(feature = "serde"))]
+                    None => {}
+                }
+                let started: Vec<_> = handler
+                    .sagas()
+                    .into_iter()
+                    .filter(|saga| states.is_enabled(*saga))
+                    .map(|saga| saga_instances.start(saga))
+                    .collect();
+                for instance in &started {
+                    commands.queue(emit(SagaStarted {
+                        name: states.name(instance.saga()),
+                        instance: *instance,
+                        event: type_name::<R>(),
+                    }));
+                }
+                if let Some(owner) = event.owner() {
+                    for instance in &started {
+                        commands.queue(own_instance(*instance, owner));
+                    }
+                }
+                started
+            }
+        };
+        for instance in instances {
+            // Other events of the instance may still be processed in this run.
+            saga_instances.hold(instance);
+            // Instances of disabled sagas finish without running their remaining steps.
+            if states.is_enabled(instance.saga()) {
+                for step in handler.of(instance.saga()) {
+                    commands.queue(step.clone().run(instance, event.clone()));
+                }
+            }
+            commands.queue(release_instance(instance));
+        }
+        for (saga, consumer) in &handler.consumers {
+            if states.is_enabled(*saga) {
+                commands.queue(consume_event(*consumer, event.clone()));
+            }
         }
     }
+    commands.queue(forget_conditions);
+}
+
+/// Runs a consumer of events outside of any saga instance.
+fn consume_event<R>(
+    consumer: SystemId<R, ()>,
+    event: R,
+) -> impl FnOnce(&mut World) -> Result<(), BevyError>
+where
+    R: SagaEvent,
+{
+    move |world| Ok(world.run_system_with(consumer, event)?)
 }
 
-pub fn send_response<Rs>(In(response): In<Rs>, mut writer: EventWriter<Rs>)
+pub fn send_response<Rs>(In(response): In<Rs>, mut writer: SagaWriter<Rs>)
 where
     Rs: Event,
 {
     writer.write(response);
 }
 
-pub fn send_option_response<Rs>(In(response): In<Option<Rs>>, mut writer: EventWriter<Rs>)
-where
+pub fn send_option_response<Rs>(
+    In(response): In<Option<Rs>>,
+    mut writer: SagaWriter<Rs>,
+    diagnostics: Option<ResMut<SagaDiagnostics>>,
+) where
     Rs: Event,
 {
-    if let Some(response) = response {
+    match response {
+        Some(response) => writer.write(response),
+        None => {
+            if let Some(mut diagnostics) = diagnostics {
+                diagnostics.count(SagaStepMetric::Dropped);
+            }
+        }
+    }
+}
+
+pub fn send_iter_response<I>(
+    In(responses): In<I>,
+    mut writer: SagaWriter<I::Item>,
+    diagnostics: Option<ResMut<SagaDiagnostics>>,
+) where
+    I: IntoIterator + 'static,
+    I::Item: Event,
+{
+    let mut responses = responses.into_iter().peekable();
+    if let (None, Some(mut diagnostics)) = (responses.peek(), diagnostics) {
+        diagnostics.count(SagaStepMetric::Dropped);
+    }
+    for response in responses {
         writer.write(response);
     }
 }
 
-pub fn send_result_response<Ok, Err>(In(result): In<Result<Ok, Err>>, mut ok_writer: EventWriter<Ok>, mut err_writer: EventWriter<Err>)
-where
+pub fn send_result_response<Ok, Err>(
+    In(result): In<Result<Ok, Err>>,
+    mut ok_writer: SagaWriter<Ok>,
+    mut err_writer: SagaWriter<Err>,
+    current: Res<CurrentSagaInstance>,
+    states: Res<SagaStates>,
+    diagnostics: Option<ResMut<SagaDiagnostics>>,
+    mut commands: Commands,
+) where
     Ok: Event,
     Err: Event,
 {
+    if let Some(mut diagnostics) = diagnostics {
+        diagnostics.count(match result {
+            Ok(_) => SagaStepMetric::Ok,
+            Err(_) => SagaStepMetric::Err,
+        });
+    }
     match result {
         Ok(ok) => {
             ok_writer.write(ok);
         },
         Err(err) => {
             err_writer.write(err);
+            if let Some(instance) = current.instance() {
+                commands.queue(emit(SagaFailed {
+                    name: states.name(instance.saga()),
+                    instance,
+                    error: type_name::<Err>(),
+                }));
+                commands.queue(compensate(instance));
+            }
         },
     }
 }
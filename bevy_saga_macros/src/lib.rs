@@ -1,4 +1,4 @@
-use crate::saga_event::{saga_event_from_enum, saga_event_from_struct};
+use crate::saga_event::{parse_saga_event_options, saga_event_from_enum, saga_event_from_struct};
 use crate::saga_router::saga_router_from_enum;
 use proc_macro::TokenStream;
 use quote::quote;
@@ -8,11 +8,20 @@ mod saga_event;
 mod saga_router;
 
 /// Used to implement the SagaEvent trait for types that are propagated through sagas. 
+///
+/// Mark the field that holds the entity owning the saga instances with `#[owner]`.
+///
+/// Write `#[saga_event(serde)]` to derive serde's `Serialize` and `Deserialize` for the event, so it
+/// can be recorded and replayed. This requires the `serde` feature of bevy_saga.
 #[proc_macro_attribute]
-pub fn saga_event(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn saga_event(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let serde = match parse_saga_event_options(attr) {
+        Ok(serde) => serde,
+        Err(error) => return error.to_compile_error().into(),
+    };
     match parse_macro_input!(item as Item) {
-        Item::Enum(enum_item) => saga_event_from_enum(enum_item),
-        Item::Struct(struct_item) => saga_event_from_struct(struct_item),
+        Item::Enum(enum_item) => saga_event_from_enum(enum_item, serde),
+        Item::Struct(struct_item) => saga_event_from_struct(struct_item, serde),
         _ => quote!{
             compile_error!("Attribute saga_event is only meant for struct or enum items.");
         },
@@ -1,22 +1,100 @@
 use quote::{quote, ToTokens};
-use syn::{ItemEnum, ItemStruct};
+use syn::{Fields, ItemEnum, ItemStruct, Member};
 
-pub fn saga_event_from_struct(struct_item: ItemStruct) -> proc_macro2::TokenStream {
+pub fn saga_event_from_struct(mut struct_item: ItemStruct, serde: bool) -> proc_macro2::TokenStream {
     let ident = struct_item.ident.clone();
-    saga_event_from_tokens(struct_item.into_token_stream(), ident)
+    let owner = match owner_field(&mut struct_item.fields) {
+        Ok(owner) => owner,
+        Err(error) => return error.to_compile_error(),
+    };
+    let owner = owner.map(|member| {
+        quote! {
+            fn owner(&self) -> Option<bevy::prelude::Entity> {
+                ::core::convert::Into::into(self.#member)
+            }
+        }
+    });
+    saga_event_from_tokens(struct_item.into_token_stream(), ident, owner, serde)
 }
 
-pub fn saga_event_from_enum(enum_item: ItemEnum) -> proc_macro2::TokenStream {
+pub fn saga_event_from_enum(enum_item: ItemEnum, serde: bool) -> proc_macro2::TokenStream {
     let ident = enum_item.ident.clone();
-    saga_event_from_tokens(enum_item.into_token_stream(), ident)
+    saga_event_from_tokens(enum_item.into_token_stream(), ident, None, serde)
+}
+
+/// Parses the options of `#[saga_event(...)]`, and returns whether the event opts into serde.
+pub fn parse_saga_event_options(attr: proc_macro::TokenStream) -> syn::Result<bool> {
+    let mut serde = false;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("serde") {
+            serde = true;
+            Ok(())
+        } else {
+            Err(meta.error("Unsupported saga_event option, expected `serde`."))
+        }
+    });
+    syn::parse::Parser::parse(parser, attr)?;
+    Ok(serde)
+}
+
+/// Finds the field that is attributed with `#[owner]` and removes the attribute.
+fn owner_field(fields: &mut Fields) -> syn::Result<Option<Member>> {
+    let mut owner = None;
+    for (index, field) in fields.iter_mut().enumerate() {
+        let attribute_count = field.attrs.len();
+        field.attrs.retain(|attribute| !attribute.path().is_ident("owner"));
+        if field.attrs.len() == attribute_count {
+            continue;
+        }
+        if owner.is_some() {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "A saga event can only have one #[owner] field.",
+            ));
+        }
+        owner = Some(match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(index.into()),
+        });
+    }
+    Ok(owner)
 }
 
-fn saga_event_from_tokens(tokens: proc_macro2::TokenStream, ident: proc_macro2::Ident) -> proc_macro2::TokenStream {
+fn saga_event_from_tokens(
+    tokens: proc_macro2::TokenStream,
+    ident: proc_macro2::Ident,
+    owner: Option<proc_macro2::TokenStream>,
+    serde: bool,
+) -> proc_macro2::TokenStream {
+    let (derive_serde, record) = if serde {
+        (
+            Some(quote! {
+                #[derive(bevy_saga_impl::serde::Serialize, bevy_saga_impl::serde::Deserialize)]
+                #[serde(crate = "bevy_saga_impl::serde")]
+            }),
+            Some(quote! {
+                fn to_record(&self) -> Option<bevy_saga_impl::serde_json::Value> {
+                    bevy_saga_impl::serde_json::to_value(self).ok()
+                }
+
+                fn from_record(
+                    value: bevy_saga_impl::serde_json::Value,
+                ) -> Option<bevy_saga_impl::serde_json::Result<Self>> {
+                    Some(bevy_saga_impl::serde_json::from_value(value))
+                }
+            }),
+        )
+    } else {
+        (None, None)
+    };
     quote! {
         #[derive(Clone, bevy::prelude::Event)]
+        #derive_serde
         #tokens
 
         impl bevy_saga_impl::SagaEvent for #ident {
+            #owner
+            #record
         }
 
         impl bevy::prelude::SystemInput for #ident {
@@ -28,4 +106,4 @@ fn saga_event_from_tokens(tokens: proc_macro2::TokenStream, ident: proc_macro2::
             }
         }
     }
-}
\ No newline at end of file
+}
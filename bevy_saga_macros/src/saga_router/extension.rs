@@ -35,6 +35,8 @@ fn generate_extension_impl(input_enum: &InputEnumMetaData) -> TokenStream {
     let method_name = extension_add_handler_method_name(input_enum);
     let enum_ident = &input_enum.enum_ident;
     let pipe_system_name = pipe_system_name(input_enum);
+    let variant_types = to_variant_types(input_enum);
+    let variant_idents = to_variant_idents(input_enum);
     quote! {
         impl #extension_trait_name for bevy::prelude::App {
             fn #method_name<R, M>(
@@ -44,13 +46,21 @@ fn generate_extension_impl(input_enum: &InputEnumMetaData) -> TokenStream {
             where
                 R: bevy_saga_impl::SagaEvent,
             {
-                self.add_event::<R>();
-                self.init_resource::<bevy_saga_impl::prelude::EventProcessors<R>>();
-                let id = self.register_system(handler.pipe(#pipe_system_name));
-                self.world_mut()
-                    .resource_mut::<bevy_saga_impl::prelude::EventProcessors<R>>()
-                    .push(id);
-                bevy::prelude::IntoScheduleConfigs::into_configs(bevy_saga_impl::prelude::process_event::<R>)
+                let handler = bevy::prelude::IntoSystem::into_system(handler);
+                let step = bevy_saga_impl::prelude::BevySagaUtil::record_saga_step::<R>(
+                    self,
+                    &bevy::ecs::system::System::name(&handler),
+                    None,
+                );
+                #(bevy_saga_impl::prelude::BevySagaUtil::record_saga_flow::<R, #variant_types>(
+                    self,
+                    step,
+                    Some(bevy_saga_impl::prelude::SagaEdgeLabel::Variant(stringify!(#variant_idents))),
+                );)*
+                bevy_saga_impl::prelude::BevySagaUtil::add_event_handler(
+                    self,
+                    bevy::prelude::IntoSystem::pipe(handler, #pipe_system_name),
+                )
             }
         }
     }
@@ -65,7 +75,7 @@ fn generate_pipe_system(input_enum: &InputEnumMetaData) -> TokenStream {
     quote! {
         fn #pipe_system_name(
             bevy::prelude::In(input_event): bevy::prelude::In<#enum_ident>,
-            #(mut #writer_parameters: bevy::prelude::EventWriter<#variant_types>,)*
+            #(mut #writer_parameters: bevy_saga_impl::prelude::SagaWriter<#variant_types>,)*
         ) {
             match input_event {
                 #(#enum_ident::#variant_idents(value) => {
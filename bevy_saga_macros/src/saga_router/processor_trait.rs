@@ -76,7 +76,11 @@ fn derive_implementation(unpack_variables: &Vec<Ident>, extension_method_name: I
             let (rs, #(#unpack_variables,)*) = self;
             bevy::prelude::IntoScheduleConfigs::into_configs((
                 app.#extension_method_name(rs),
-                #(bevy_saga_impl::prelude::BevySagaUtil::add_event_handler(app, #unpack_variables),)*
+                #(bevy_saga_impl::prelude::BevySagaUtil::add_step_handler(
+                    app,
+                    #unpack_variables,
+                    Some(bevy_saga_impl::prelude::SagaEdgeLabel::Sibling),
+                ),)*
             ))
         }
     }